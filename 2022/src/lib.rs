@@ -32,9 +32,8 @@ pub fn nom_parse<'a, O>(
     let nom_parse_result = parser(input);
 
     nom_parse_result.map(|(_i, o)| o).map_err(|e| match e {
-        Err::Error(e) => anyhow!("Parse errors:\n{}", convert_error(input, e)),
-        Err::Incomplete(_e) => anyhow!("Parse error: incomplete input"),
-        Err::Failure(_e) => anyhow!("Parse error: failure"),
+        Err::Error(e) | Err::Failure(e) => anyhow!("Parse errors:\n{}", convert_error(input, e)),
+        Err::Incomplete(needed) => anyhow!("Parse error: incomplete input ({:?})", needed),
     })
 }
 
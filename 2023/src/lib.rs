@@ -31,9 +31,8 @@ pub fn nom_parse<'a, O>(
     let nom_parse_result = parser(input);
 
     nom_parse_result.map(|(_i, o)| o).map_err(|e| match e {
-        Err::Error(e) => eyre!("Parse errors:\n{}", convert_error(input, e)),
-        Err::Incomplete(_e) => eyre!("Parse error: incomplete input"),
-        Err::Failure(_e) => eyre!("Parse error: failure"),
+        Err::Error(e) | Err::Failure(e) => eyre!("Parse errors:\n{}", convert_error(input, e)),
+        Err::Incomplete(needed) => eyre!("Parse error: incomplete input ({:?})", needed),
     })
 }
 
@@ -312,7 +311,7 @@ impl<'a> Iterator for StringColumns<'a> {
         self.scratch_space.clear();
 
         for line in &self.lines {
-            if let Some(ch) = line.chars().nth(self.column_index) {
+            if let Some(ch) = line.get_ascii(self.column_index) {
                 self.scratch_space.push(ch.as_char());
             } else {
                 self.scratch_space.push('\0');
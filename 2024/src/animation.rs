@@ -0,0 +1,325 @@
+/// Recording and terminal playback of grid simulations
+use crate::*;
+use std::fmt::Write as _;
+use std::io::{BufRead, Write as _};
+use std::path::Path;
+use std::sync::{mpsc, Mutex, OnceLock};
+use std::time::Duration;
+
+/// Collects frames of a simulation so they can be watched in the terminal or written to a file.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let mut recorder = Recorder::default();
+/// let mut grid = CharGrid::from_text("...\n...").unwrap();
+/// for col in 0..3 {
+///     grid[(0, col)] = '#';
+///     recorder.record(&grid, |&c| c);
+/// }
+///
+/// assert_eq!(recorder.frames().len(), 3);
+/// assert_eq!(recorder.frames()[1], "##.\n...\n");
+/// // recorder.play(10.0) to watch it
+/// ```
+#[derive(Debug, Default, Clone)]
+pub struct Recorder {
+    frames: Vec<String>,
+}
+
+impl Recorder {
+    pub fn record<T>(&mut self, grid: &Array2D<T>, cell_to_char: impl Fn(&T) -> char) {
+        self.frames.push(grid.render(cell_to_char).to_string());
+    }
+
+    /// Records a sparse grid, such as a set of occupied positions. The frame covers the bounding
+    /// box of `cells`; all other positions are drawn as `.`.
+    pub fn record_sparse(&mut self, cells: impl IntoIterator<Item = Idx>, glyph: char) {
+        let cells: HashSet<Idx> = cells.into_iter().collect();
+        if cells.is_empty() {
+            self.frames.push(String::new());
+            return;
+        }
+
+        let (min_row, max_row) = cells.iter().map(|p| p.0).minmax().into_option().unwrap();
+        let (min_col, max_col) = cells.iter().map(|p| p.1).minmax().into_option().unwrap();
+
+        let mut frame = String::new();
+        for row in min_row..=max_row {
+            for col in min_col..=max_col {
+                frame.push(if cells.contains(&(row, col)) {
+                    glyph
+                } else {
+                    '.'
+                });
+            }
+            frame.push('\n');
+        }
+
+        self.frames.push(frame);
+    }
+
+    /// Records an already rendered frame, e.g. the output of [`Array2D::render`].
+    pub fn record_text(&mut self, frame: impl ToString) {
+        self.frames.push(frame.to_string());
+    }
+
+    pub fn frames(&self) -> &[String] {
+        &self.frames
+    }
+
+    /// Plays back the frames in the terminal at `fps` frames per second.
+    ///
+    /// While playing, the following commands can be entered (followed by Enter):
+    /// `p` pauses or resumes, an empty line steps forward, `b` steps back, `+` and `-` change
+    /// the speed and `q` quits.
+    pub fn play(&self, fps: f64) -> Result<()> {
+        if self.frames.is_empty() {
+            return Ok(());
+        }
+
+        let commands = stdin_commands().lock().unwrap_or_else(|e| e.into_inner());
+        // Drop anything that was typed while nothing was playing
+        while commands.try_recv().is_ok() {}
+
+        let mut playback = Playback::new(self.frames.len(), fps);
+        let mut stdout = std::io::stdout();
+
+        loop {
+            write!(
+                stdout,
+                "\x1b[2J\x1b[H{}\nframe {}/{} | {:.1} fps{}\n",
+                self.frames[playback.frame],
+                playback.frame + 1,
+                self.frames.len(),
+                playback.fps,
+                if playback.paused { " | paused" } else { "" },
+            )?;
+            stdout.flush()?;
+
+            let command = if playback.paused {
+                match commands.recv() {
+                    Ok(command) => Some(command),
+                    Err(mpsc::RecvError) => {
+                        // stdin is closed, so nothing can resume playback: keep playing
+                        std::thread::sleep(playback.delay());
+                        None
+                    }
+                }
+            } else {
+                match commands.recv_timeout(playback.delay()) {
+                    Ok(command) => Some(command),
+                    Err(mpsc::RecvTimeoutError::Timeout) => None,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        // stdin is closed, keep playing at the same pace
+                        std::thread::sleep(playback.delay());
+                        None
+                    }
+                }
+            };
+
+            if !playback.apply(command.as_deref()) {
+                return Ok(());
+            }
+        }
+    }
+
+    /// Writes all frames to a plain text file, each preceded by a `--- frame N ---` header.
+    pub fn write_text(&self, path: impl AsRef<Path>) -> Result<()> {
+        let mut out = String::new();
+        for (i, frame) in self.frames.iter().enumerate() {
+            writeln!(out, "--- frame {} ---", i + 1)?;
+            out.push_str(frame);
+        }
+
+        std::fs::write(path.as_ref(), out)
+            .wrap_err_with(|| format!("while writing {}", path.as_ref().display()))
+    }
+
+    /// Writes the frames as an asciicast (v2) recording, to be replayed with `asciinema play`.
+    pub fn write_asciicast(&self, path: impl AsRef<Path>, fps: f64) -> Result<()> {
+        std::fs::write(path.as_ref(), self.to_asciicast(fps))
+            .wrap_err_with(|| format!("while writing {}", path.as_ref().display()))
+    }
+
+    fn to_asciicast(&self, fps: f64) -> String {
+        let fps = fps.max(MIN_FPS);
+        let width = self
+            .frames
+            .iter()
+            .flat_map(|f| f.lines())
+            .map(|l| l.chars().count())
+            .max()
+            .unwrap_or(0);
+        let height = self
+            .frames
+            .iter()
+            .map(|f| f.lines().count())
+            .max()
+            .unwrap_or(0);
+
+        let mut out = format!(
+            "{{\"version\": 2, \"width\": {}, \"height\": {}}}\n",
+            width.max(1),
+            height.max(1)
+        );
+        for (i, frame) in self.frames.iter().enumerate() {
+            let data = format!("\x1b[2J\x1b[H{}", frame.replace('\n', "\r\n"));
+            out.push_str(&format!(
+                "[{:.3}, \"o\", {}]\n",
+                i as f64 / fps,
+                json_string(&data)
+            ));
+        }
+
+        out
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\r' => out.push_str("\\r"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+/// The lines entered on stdin. They are read by a single background thread, started on first
+/// use, so that consecutive playbacks do not compete for the input.
+fn stdin_commands() -> &'static Mutex<mpsc::Receiver<String>> {
+    static COMMANDS: OnceLock<Mutex<mpsc::Receiver<String>>> = OnceLock::new();
+
+    COMMANDS.get_or_init(|| {
+        let (sender, receiver) = mpsc::channel();
+        std::thread::spawn(move || {
+            for line in std::io::stdin().lock().lines() {
+                let Ok(line) = line else { break };
+                if sender.send(line.trim().to_string()).is_err() {
+                    break;
+                }
+            }
+        });
+
+        Mutex::new(receiver)
+    })
+}
+
+const MIN_FPS: f64 = 0.1;
+
+/// Playback state, separated from the terminal so it can be tested.
+struct Playback {
+    num_frames: usize,
+    frame: usize,
+    fps: f64,
+    paused: bool,
+}
+
+impl Playback {
+    fn new(num_frames: usize, fps: f64) -> Self {
+        Playback {
+            num_frames,
+            frame: 0,
+            fps: fps.max(MIN_FPS),
+            paused: false,
+        }
+    }
+
+    fn delay(&self) -> Duration {
+        Duration::from_secs_f64(1.0 / self.fps)
+    }
+
+    /// Applies a command, or advances a frame when there is none. Returns false when playback
+    /// should stop.
+    fn apply(&mut self, command: Option<&str>) -> bool {
+        match command {
+            None | Some("") => {
+                if self.frame + 1 == self.num_frames {
+                    // Stay on the last frame when stepping through it manually
+                    return command.is_some() && self.paused;
+                }
+                self.frame += 1;
+            }
+            Some("b") => self.frame = self.frame.saturating_sub(1),
+            Some("p") => self.paused = !self.paused,
+            Some("+") => self.fps *= 2.0,
+            Some("-") => self.fps = (self.fps / 2.0).max(MIN_FPS),
+            Some("q") => return false,
+            Some(_) => {}
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_record_sparse_uses_bounding_box() {
+        let mut recorder = Recorder::default();
+        recorder.record_sparse([(-1, 2), (0, 4)], '#');
+
+        assert_eq!(recorder.frames(), ["#..\n..#\n"]);
+    }
+
+    #[test]
+    pub fn test_asciicast_format() {
+        let mut recorder = Recorder::default();
+        recorder.record_text("ab\n");
+        recorder.record_text("cd\n");
+
+        assert_eq!(
+            recorder.to_asciicast(2.0),
+            concat!(
+                "{\"version\": 2, \"width\": 2, \"height\": 1}\n",
+                "[0.000, \"o\", \"\\u001b[2J\\u001b[Hab\\r\\n\"]\n",
+                "[0.500, \"o\", \"\\u001b[2J\\u001b[Hcd\\r\\n\"]\n",
+            )
+        );
+    }
+
+    #[test]
+    pub fn test_asciicast_clamps_fps() {
+        let mut recorder = Recorder::default();
+        recorder.record_text("ab\n");
+        recorder.record_text("cd\n");
+
+        for fps in [0.0, -1.0] {
+            let cast = recorder.to_asciicast(fps);
+            assert!(cast.contains("[10.000, "), "{}", cast);
+        }
+    }
+
+    #[test]
+    pub fn test_playback_controls() {
+        let mut playback = Playback::new(3, 10.0);
+
+        assert!(playback.apply(Some("p")));
+        assert!(playback.paused);
+        assert!(playback.apply(Some("")));
+        assert_eq!(playback.frame, 1);
+        assert!(playback.apply(Some("b")));
+        assert_eq!(playback.frame, 0);
+        assert!(playback.apply(Some("+")));
+        assert_eq!(playback.fps, 20.0);
+
+        assert!(playback.apply(Some("p")));
+        assert!(playback.apply(None));
+        assert!(playback.apply(None));
+        assert!(
+            !playback.apply(None),
+            "playback should stop after the last frame"
+        );
+    }
+}
@@ -1,3 +1,5 @@
+use crate::Result;
+use eyre::eyre;
 use std::fmt::{Debug, Formatter};
 use std::ops::{Index, IndexMut};
 
@@ -20,7 +22,7 @@ use std::ops::{Index, IndexMut};
 /// assert_eq!(array[(1, 1)], 5);
 /// assert_eq!(array[(1, 2)], 6);
 /// ```
-#[derive(Clone, Eq, PartialEq)]
+#[derive(Clone, Eq, PartialEq, Hash)]
 pub struct Array2D<T> {
     shape: (i64, i64),
     data: Vec<T>,
@@ -28,21 +30,43 @@ pub struct Array2D<T> {
 
 pub type Idx = (i64, i64);
 
+pub(crate) const VON_NEUMANN_NEIGHBORHOOD: [Idx; 4] = [
+    (0, -1), // up
+    (-1, 0), // left
+    (1, 0),  // right
+    (0, 1),  // down
+];
+
+pub(crate) const MOORE_NEIGHBORHOOD: [Idx; 8] = [
+    (-1, -1), // top-left
+    (0, -1),  // top
+    (1, -1),  // top-right
+    (-1, 0),  // left
+    (1, 0),   // right
+    (-1, 1),  // bottom-left
+    (0, 1),   // bottom
+    (1, 1),   // bottom-right
+];
+
 impl<T> Array2D<T> {
     pub fn from_shape_vec(shape: (i64, i64), data: Vec<T>) -> Self {
+        Self::try_from_shape_vec(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_shape_vec(shape: (i64, i64), data: Vec<T>) -> Result<Self> {
         if shape.0 <= 0 || shape.1 <= 0 {
-            panic!("invalid shape {:?}", shape);
+            return Err(eyre!("invalid shape {:?}", shape));
         }
 
         if (shape.0 * shape.1) as usize != data.len() {
-            panic!(
+            return Err(eyre!(
                 "shape {:?} does not match data length {}",
                 shape,
                 data.len()
-            );
+            ));
         }
 
-        Self { shape, data }
+        Ok(Self { shape, data })
     }
 
     pub fn nrows(&self) -> i64 {
@@ -69,7 +93,7 @@ impl<T> Array2D<T> {
         &mut self.data
     }
 
-    pub fn rows(&self) -> Array2DRowsIter<T> {
+    pub fn rows(&self) -> Array2DRowsIter<'_, T> {
         Array2DRowsIter {
             array: self,
             row: 0,
@@ -81,12 +105,7 @@ impl<T> Array2D<T> {
     }
 
     pub fn indices_row_major(&self) -> Array2DIndicesIter {
-        Array2DIndicesIter {
-            shape: self.shape,
-            row: 0,
-            col: 0,
-            order: Order::RowMajor,
-        }
+        Array2DIndicesIter::row_major(self.shape)
     }
 
     pub fn indices_col_major(&self) -> Array2DIndicesIter {
@@ -98,21 +117,21 @@ impl<T> Array2D<T> {
         }
     }
 
-    pub fn indexed_iter(&self) -> Array2DIndexedIter<T> {
+    pub fn indexed_iter(&self) -> Array2DIndexedIter<'_, T> {
         Array2DIndexedIter {
             array: self,
             indices_iter: self.indices(),
         }
     }
 
-    pub fn iter_row_major(&self) -> Array2DIter<T> {
+    pub fn iter_row_major(&self) -> Array2DIter<'_, T> {
         Array2DIter {
             array: self,
             indices_iter: self.indices_row_major(),
         }
     }
 
-    pub fn iter_col_major(&self) -> Array2DIter<T> {
+    pub fn iter_col_major(&self) -> Array2DIter<'_, T> {
         Array2DIter {
             array: self,
             indices_iter: self.indices_col_major(),
@@ -143,6 +162,24 @@ impl<T: Clone> Array2D<T> {
         }
     }
 
+    /// Mirrors the array left-to-right.
+    pub fn flipped(&self) -> Self {
+        let data = self
+            .rows()
+            .flat_map(|row| row.iter().rev().cloned())
+            .collect();
+
+        Self {
+            shape: self.shape,
+            data,
+        }
+    }
+
+    /// Rotates the array a quarter turn clockwise.
+    pub fn rotated(&self) -> Self {
+        self.transposed().flipped()
+    }
+
     pub fn step_from(&self, init: Idx, step: Idx) -> impl Iterator<Item = T> + '_ {
         let mut current = init;
 
@@ -158,7 +195,7 @@ impl<T: Clone> Array2D<T> {
         })
     }
 
-    pub fn cols(&self) -> Array2DColsIter<T> {
+    pub fn cols(&self) -> Array2DColsIter<'_, T> {
         Array2DColsIter {
             array: self,
             col: 0,
@@ -170,17 +207,10 @@ impl<T: Clone> Array2D<T> {
             .map(|(_pos, value)| value)
     }
 
-    pub fn indexed_von_neumann_neighborhood(&self, pos: Idx) -> RelativeIter<4, T> {
-        let neighborhood: [Idx; 4] = [
-            (0, -1), // up
-            (-1, 0), // left
-            (1, 0),  // right
-            (0, 1),  // down
-        ];
-
+    pub fn indexed_von_neumann_neighborhood(&self, pos: Idx) -> RelativeIter<'_, 4, T> {
         RelativeIter {
             array: self,
-            offsets: neighborhood,
+            offsets: VON_NEUMANN_NEIGHBORHOOD,
             offsets_idx: 0,
             relative_from: pos,
         }
@@ -191,21 +221,10 @@ impl<T: Clone> Array2D<T> {
             .map(|(_pos, value)| value)
     }
 
-    pub fn indexed_moore_neighborhood(&self, pos: Idx) -> RelativeIter<8, T> {
-        let neighborhood: [Idx; 8] = [
-            (-1, -1), // top-left
-            (0, -1),  // top
-            (1, -1),  // top-right
-            (-1, 0),  // left
-            (1, 0),   // right
-            (-1, 1),  // bottom-left
-            (0, 1),   // bottom
-            (1, 1),   // bottom-right
-        ];
-
+    pub fn indexed_moore_neighborhood(&self, pos: Idx) -> RelativeIter<'_, 8, T> {
         RelativeIter {
             array: self,
-            offsets: neighborhood,
+            offsets: MOORE_NEIGHBORHOOD,
             offsets_idx: 0,
             relative_from: pos,
         }
@@ -316,6 +335,17 @@ pub struct Array2DIndicesIter {
     order: Order,
 }
 
+impl Array2DIndicesIter {
+    pub(crate) fn row_major(shape: (i64, i64)) -> Self {
+        Array2DIndicesIter {
+            shape,
+            row: 0,
+            col: 0,
+            order: Order::RowMajor,
+        }
+    }
+}
+
 impl Iterator for Array2DIndicesIter {
     type Item = Idx;
 
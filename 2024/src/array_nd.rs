@@ -0,0 +1,374 @@
+/// N-dimensional arrays
+use crate::{Array2D, Result};
+use eyre::eyre;
+use std::ops::{Index, IndexMut};
+
+/// A D-dimensional array stored in a single contiguous buffer (row-major order, i.e. the last
+/// axis varies fastest).
+///
+/// Example:
+/// ```
+/// use aoc2024::ArrayND;
+///
+/// let mut cube = ArrayND::from_elem([2, 3, 4], 0);
+/// cube[[1, 2, 3]] = 7;
+///
+/// assert_eq!(cube.len(), 24);
+/// assert_eq!(cube.data()[23], 7);
+/// assert!(cube.in_bounds([1, 2, 3]));
+/// assert!(!cube.in_bounds([2, 0, 0]));
+/// ```
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ArrayND<T, const D: usize> {
+    shape: [i64; D],
+    data: Vec<T>,
+}
+
+pub type IdxND<const D: usize> = [i64; D];
+
+impl<T, const D: usize> ArrayND<T, D> {
+    pub fn from_shape_vec(shape: [i64; D], data: Vec<T>) -> Self {
+        Self::try_from_shape_vec(shape, data).unwrap_or_else(|e| panic!("{}", e))
+    }
+
+    pub fn try_from_shape_vec(shape: [i64; D], data: Vec<T>) -> Result<Self> {
+        if D == 0 || shape.iter().any(|&len| len <= 0) {
+            return Err(eyre!("invalid shape {:?}", shape));
+        }
+
+        if shape.iter().product::<i64>() as usize != data.len() {
+            return Err(eyre!(
+                "shape {:?} does not match data length {}",
+                shape,
+                data.len()
+            ));
+        }
+
+        Ok(Self { shape, data })
+    }
+
+    pub fn shape(&self) -> [i64; D] {
+        self.shape
+    }
+
+    pub fn len(&self) -> usize {
+        self.data.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn in_bounds(&self, idx: IdxND<D>) -> bool {
+        idx.iter()
+            .zip(self.shape.iter())
+            .all(|(&i, &len)| i >= 0 && i < len)
+    }
+
+    pub fn data(&self) -> &[T] {
+        &self.data
+    }
+
+    pub fn data_mut(&mut self) -> &mut [T] {
+        &mut self.data
+    }
+
+    pub fn into_data(self) -> Vec<T> {
+        self.data
+    }
+
+    pub fn at(&self, idx: IdxND<D>) -> Option<&T> {
+        if self.in_bounds(idx) {
+            Some(&self[idx])
+        } else {
+            None
+        }
+    }
+
+    pub fn at_mut(&mut self, idx: IdxND<D>) -> Option<&mut T> {
+        if self.in_bounds(idx) {
+            Some(&mut self[idx])
+        } else {
+            None
+        }
+    }
+
+    pub fn indices(&self) -> ArrayNDIndicesIter<D> {
+        ArrayNDIndicesIter {
+            shape: self.shape,
+            next: Some([0; D]),
+            remaining: self.data.len(),
+        }
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (IdxND<D>, &T)> + '_ {
+        self.indices().zip(self.data.iter())
+    }
+
+    /// Converts a flat offset into `data` back into an index.
+    pub fn unravel(&self, mut offset: usize) -> IdxND<D> {
+        let mut idx = [0; D];
+        for axis in (0..D).rev() {
+            let len = self.shape[axis] as usize;
+            idx[axis] = (offset % len) as i64;
+            offset /= len;
+        }
+
+        idx
+    }
+
+    fn offset(&self, idx: IdxND<D>) -> usize {
+        idx.iter()
+            .zip(self.shape.iter())
+            .fold(0, |offset, (&i, &len)| offset * len + i) as usize
+    }
+}
+
+impl<T: Clone, const D: usize> ArrayND<T, D> {
+    pub fn from_elem(shape: [i64; D], elem: T) -> Self {
+        let len = shape.iter().map(|&len| len.max(0)).product::<i64>() as usize;
+
+        Self::from_shape_vec(shape, vec![elem; len])
+    }
+
+    /// The up to 2·D cells that differ from `pos` by one along exactly one axis.
+    pub fn von_neumann_neighborhood(&self, pos: IdxND<D>) -> impl Iterator<Item = T> + '_ {
+        self.indexed_von_neumann_neighborhood(pos)
+            .map(|(_pos, value)| value)
+    }
+
+    pub fn indexed_von_neumann_neighborhood(
+        &self,
+        pos: IdxND<D>,
+    ) -> impl Iterator<Item = (IdxND<D>, T)> + '_ {
+        self.relative(pos, von_neumann_offsets())
+    }
+
+    /// The up to 3^D−1 cells that differ from `pos` by at most one along every axis.
+    pub fn moore_neighborhood(&self, pos: IdxND<D>) -> impl Iterator<Item = T> + '_ {
+        self.indexed_moore_neighborhood(pos)
+            .map(|(_pos, value)| value)
+    }
+
+    pub fn indexed_moore_neighborhood(
+        &self,
+        pos: IdxND<D>,
+    ) -> impl Iterator<Item = (IdxND<D>, T)> + '_ {
+        self.relative(pos, moore_offsets())
+    }
+
+    fn relative(
+        &self,
+        pos: IdxND<D>,
+        offsets: Vec<IdxND<D>>,
+    ) -> impl Iterator<Item = (IdxND<D>, T)> + '_ {
+        offsets.into_iter().filter_map(move |offset| {
+            let mut next_pos = pos;
+            for axis in 0..D {
+                next_pos[axis] += offset[axis];
+            }
+
+            self.at(next_pos).map(|value| (next_pos, value.clone()))
+        })
+    }
+}
+
+/// Offsets of the 2·D orthogonal neighbours of a cell.
+pub fn von_neumann_offsets<const D: usize>() -> Vec<IdxND<D>> {
+    let mut offsets = Vec::with_capacity(2 * D);
+    for axis in 0..D {
+        for delta in [-1, 1] {
+            let mut offset = [0; D];
+            offset[axis] = delta;
+            offsets.push(offset);
+        }
+    }
+
+    offsets
+}
+
+/// Offsets of the 3^D−1 neighbours of a cell, including diagonals.
+pub fn moore_offsets<const D: usize>() -> Vec<IdxND<D>> {
+    let mut offsets = Vec::with_capacity(3usize.pow(D as u32) - 1);
+    let mut offset = [-1; D];
+    loop {
+        if offset.iter().any(|&d| d != 0) {
+            offsets.push(offset);
+        }
+
+        // Count in base 3 over {-1, 0, 1}
+        let mut axis = D;
+        loop {
+            if axis == 0 {
+                return offsets;
+            }
+            axis -= 1;
+
+            if offset[axis] < 1 {
+                offset[axis] += 1;
+                break;
+            }
+            offset[axis] = -1;
+        }
+    }
+}
+
+impl<T, const D: usize> Index<IdxND<D>> for ArrayND<T, D> {
+    type Output = T;
+
+    fn index(&self, index: IdxND<D>) -> &Self::Output {
+        if !self.in_bounds(index) {
+            panic!("index {:?} out of bounds for shape {:?}", index, self.shape);
+        }
+
+        &self.data[self.offset(index)]
+    }
+}
+
+impl<T, const D: usize> IndexMut<IdxND<D>> for ArrayND<T, D> {
+    fn index_mut(&mut self, index: IdxND<D>) -> &mut Self::Output {
+        if !self.in_bounds(index) {
+            panic!("index {:?} out of bounds for shape {:?}", index, self.shape);
+        }
+
+        let offset = self.offset(index);
+        &mut self.data[offset]
+    }
+}
+
+impl<T, const D: usize> IntoIterator for ArrayND<T, D> {
+    type Item = T;
+    type IntoIter = std::vec::IntoIter<Self::Item>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.data.into_iter()
+    }
+}
+
+impl<T> From<Array2D<T>> for ArrayND<T, 2> {
+    fn from(array: Array2D<T>) -> Self {
+        let (nrows, ncols) = array.shape();
+
+        Self::from_shape_vec([nrows, ncols], array.into_iter().collect())
+    }
+}
+
+impl<T> From<ArrayND<T, 2>> for Array2D<T> {
+    fn from(array: ArrayND<T, 2>) -> Self {
+        let [nrows, ncols] = array.shape;
+
+        Self::from_shape_vec((nrows, ncols), array.data)
+    }
+}
+
+pub struct ArrayNDIndicesIter<const D: usize> {
+    shape: [i64; D],
+    next: Option<IdxND<D>>,
+    remaining: usize,
+}
+
+impl<const D: usize> Iterator for ArrayNDIndicesIter<D> {
+    type Item = IdxND<D>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let idx = self.next?;
+        self.remaining -= 1;
+
+        let mut next = idx;
+        let mut axis = D;
+        self.next = loop {
+            if axis == 0 {
+                break None;
+            }
+            axis -= 1;
+
+            next[axis] += 1;
+            if next[axis] < self.shape[axis] {
+                break Some(next);
+            }
+            next[axis] = 0;
+        };
+
+        Some(idx)
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (self.remaining, Some(self.remaining))
+    }
+}
+
+#[rustfmt::skip]
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Itertools;
+
+    #[test]
+    pub fn test_indexing_is_row_major() {
+        let array = ArrayND::from_shape_vec([2, 2, 3], (0..12).collect_vec());
+
+        assert_eq!(array[[0, 0, 0]], 0);
+        assert_eq!(array[[0, 0, 2]], 2);
+        assert_eq!(array[[0, 1, 0]], 3);
+        assert_eq!(array[[1, 0, 0]], 6);
+        assert_eq!(array[[1, 1, 2]], 11);
+    }
+
+    #[test]
+    #[should_panic]
+    pub fn test_shape_mismatch_panics() {
+        ArrayND::from_shape_vec([2, 2, 2], vec![0; 7]);
+    }
+
+    #[test]
+    pub fn test_try_from_shape_vec() {
+        assert!(ArrayND::try_from_shape_vec([2, 3], vec![0; 6]).is_ok());
+        assert!(ArrayND::try_from_shape_vec([2, 3], vec![0; 5]).is_err());
+        assert!(ArrayND::try_from_shape_vec([2, 0], Vec::<i32>::new()).is_err());
+        assert!(ArrayND::try_from_shape_vec([], vec![0]).is_err());
+    }
+
+    #[test]
+    pub fn test_indices_match_unravel() {
+        let array = ArrayND::from_elem([3, 1, 2, 2], 0);
+
+        let indices = array.indices().collect_vec();
+
+        assert_eq!(indices.len(), 12);
+        assert_eq!(indices[0], [0, 0, 0, 0]);
+        assert_eq!(indices[1], [0, 0, 0, 1]);
+        assert_eq!(indices[2], [0, 0, 1, 0]);
+        assert_eq!(indices[11], [2, 0, 1, 1]);
+        for (offset, idx) in indices.into_iter().enumerate() {
+            assert_eq!(array.unravel(offset), idx);
+        }
+    }
+
+    #[test]
+    pub fn test_neighborhood_sizes() {
+        assert_eq!(von_neumann_offsets::<3>().len(), 6);
+        assert_eq!(moore_offsets::<3>().len(), 26);
+        assert_eq!(moore_offsets::<4>().len(), 80);
+        assert_eq!(moore_offsets::<4>().into_iter().unique().count(), 80);
+
+        let array = ArrayND::from_elem([3, 3, 3], 1);
+        assert_eq!(array.von_neumann_neighborhood([1, 1, 1]).count(), 6);
+        assert_eq!(array.moore_neighborhood([1, 1, 1]).count(), 26);
+        assert_eq!(array.von_neumann_neighborhood([0, 0, 0]).count(), 3);
+        assert_eq!(array.moore_neighborhood([0, 0, 0]).count(), 7);
+    }
+
+    #[test]
+    pub fn test_convert_from_and_to_array_2d() {
+        let array = Array2D::from_shape_vec((2, 3), vec![
+            1, 2, 3,
+            4, 5, 6,
+        ]);
+
+        let nd: ArrayND<_, 2> = array.clone().into();
+        assert_eq!(nd.shape(), [2, 3]);
+        assert_eq!(nd[[1, 0]], 4);
+
+        assert_eq!(Array2D::from(nd), array);
+    }
+}
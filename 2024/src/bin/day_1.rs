@@ -48,10 +48,10 @@ fn task_2(input: &str) -> Result<i64> {
         right_list.push(right);
     }
 
-    let right_list_counts: HashMap<i64, i64> = right_list.into_iter().frequencies();
+    let right_list_counts: Counter<i64> = right_list.into_iter().frequencies();
     let similarity_score = left_list
         .iter()
-        .map(|&left| left * right_list_counts.get(&left).unwrap_or(&0));
+        .map(|&left| left * right_list_counts.count(&left));
 
     Ok(similarity_score.sum())
 }
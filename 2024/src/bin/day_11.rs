@@ -12,44 +12,40 @@ aoc_main!(
 );
 
 fn task_1(input: &str) -> Result<i64> {
-    let mut stones = parse_nums(input)?.iter().map(|&n| (n, 1)).collect();
+    let mut stones = parse_nums(input)?.into_iter().frequencies();
 
     for _ in 0..25 {
-        stones = simulate_blink(&mut stones);
+        stones = simulate_blink(&stones);
     }
 
-    Ok(stones.values().sum())
+    Ok(stones.total())
 }
 
 fn task_2(input: &str) -> Result<i64> {
-    let mut stones = parse_nums(input)?.iter().map(|&n| (n, 1)).collect();
+    let mut stones = parse_nums(input)?.into_iter().frequencies();
 
     for _ in 0..75 {
-        stones = simulate_blink(&mut stones);
+        stones = simulate_blink(&stones);
     }
 
-    Ok(stones.values().sum())
+    Ok(stones.total())
 }
 
-fn simulate_blink(stones: &mut HashMap<i64, i64>) -> HashMap<i64, i64> {
-    let mut next = HashMap::default();
-    for (&stone, &amount) in stones.iter() {
+fn simulate_blink(stones: &Counter<i64>) -> Counter<i64> {
+    stones.flat_map(|&stone| {
         if stone == 0 {
-            *next.entry(1).or_insert(0) += amount;
+            vec![1]
         } else if let Some((left, right)) = split_if_even_digits(stone) {
-            *next.entry(left).or_insert(0) += amount;
-            *next.entry(right).or_insert(0) += amount;
+            vec![left, right]
         } else {
-            *next.entry(stone * 2024).or_insert(0) += amount;
+            vec![stone * 2024]
         }
-    }
-
-    next
+    })
 }
 
 fn split_if_even_digits(n: i64) -> Option<(i64, i64)> {
     let n_digits = n.ilog10() + 1;
-    if n_digits % 2 == 0 {
+    if n_digits.is_multiple_of(2) {
         let half = n_digits / 2;
 
         let left = n / (10i64.pow(half));
@@ -23,64 +23,18 @@ MXMXAXMASX
 fn task_1(input: &str) -> Result<i64> {
     let word_search = CharGrid::from_text(input)?;
 
-    #[rustfmt::skip]
-    let search_dirs = [
-        (-1, -1), (-1, 0), (-1, 1),
-        (0, -1),           (0, 1), 
-        (1, -1),  (1, 0),  (1, 1),
-     ];
-
-    let mut matches = 0;
-    for row in 0..word_search.nrows() {
-        for col in 0..word_search.ncols() {
-            if word_search[(row, col)] != 'X' {
-                continue;
-            }
-
-            for dir in search_dirs {
-                let potential_match = word_search.step_from((row, col), dir).take("MAS".len());
-
-                if itertools::equal(potential_match, "MAS".chars()) {
-                    matches += 1;
-                }
-            }
-        }
-    }
-
-    Ok(matches)
+    Ok(word_search.find_word("XMAS").len() as i64)
 }
 
 fn task_2(input: &str) -> Result<i64> {
-    let options = [
-        // top-left, top-right, bottom-left, bottom-right
-        ['M', 'M', 'S', 'S'],
-        ['M', 'S', 'M', 'S'],
-        ['S', 'M', 'S', 'M'],
-        ['S', 'S', 'M', 'M'],
-    ];
-
     let word_search = CharGrid::from_text(input)?;
+    let x_mas = Stencil::from_text("M?S\n?A?\nM?S", '?')?;
 
-    let mut matches = 0;
-    for row in 1..(word_search.nrows() - 1) {
-        for col in 1..(word_search.ncols() - 1) {
-            if word_search[(row, col)] != 'A' {
-                // A is always in the middle
-                continue;
-            }
-
-            let letters = [
-                word_search[(row - 1, col - 1)],
-                word_search[(row - 1, col + 1)],
-                word_search[(row + 1, col - 1)],
-                word_search[(row + 1, col + 1)],
-            ];
-
-            if options.contains(&letters) {
-                matches += 1;
-            }
-        }
-    }
+    let matches = x_mas
+        .orientations()
+        .iter()
+        .map(|stencil| word_search.find_stencil(stencil).len())
+        .sum::<usize>();
 
-    Ok(matches)
+    Ok(matches as i64)
 }
@@ -73,19 +73,19 @@ type RuleMap = HashMap<i64, Vec<i64>>;
 type ParsedInput = (Vec<Vec<i64>>, RuleMap);
 
 fn parse(input: &str) -> Result<ParsedInput> {
-    let (page_order_rules_part, page_updates_part) =
-        input.split_once("\n\n").ok_or_parse_error()?;
+    let [page_order_rules_part, page_updates_part] = sections_n(input)?;
 
     let mut rules: HashMap<i64, Vec<i64>> = HashMap::default();
 
-    for rule in page_order_rules_part.lines() {
+    for rule in page_order_rules_part.lines {
         let (first, second) = parse_num_pair(rule)?;
 
         rules.entry(first).or_default().push(second);
     }
 
     let pages_to_produce_in_each_update: Vec<Vec<i64>> = page_updates_part
-        .lines()
+        .lines
+        .into_iter()
         .map(parse_nums)
         .collect::<Result<_>>()?;
 
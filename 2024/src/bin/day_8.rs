@@ -39,8 +39,8 @@ fn task_1(input: &str) -> Result<usize> {
     let mut antinode_locations: HashSet<(i64, i64)> = HashSet::default();
 
     let antenna_pairs = frequencies
-        .iter()
-        .flat_map(|(_freq, locations)| locations.iter().tuple_combinations());
+        .values()
+        .flat_map(|locations| locations.iter().tuple_combinations());
 
     for (a, b) in antenna_pairs {
         let dist_a_to_b = (b.0 - a.0, b.1 - a.1);
@@ -75,8 +75,8 @@ fn task_2(input: &str) -> Result<usize> {
     }
 
     let antenna_pairs = frequencies
-        .iter()
-        .flat_map(|(_freq, locations)| locations.iter().tuple_combinations());
+        .values()
+        .flat_map(|locations| locations.iter().tuple_combinations());
 
     let unique_antinode_locations = antenna_pairs
         .flat_map(|(a, b)| points_on_line(&antenna_map, *a, *b))
@@ -91,8 +91,8 @@ fn points_on_line(
     to: (i64, i64),
 ) -> impl Iterator<Item = (i64, i64)> + '_ {
     let (dy, dx) = (to.0 - from.0, to.1 - from.1);
-    let gcd = euclid_gcd(dy, dx);
-    let (dy, dx) = (dy / gcd, dx / gcd);
+    let divisor = gcd(dy, dx);
+    let (dy, dx) = (dy / divisor, dx / divisor);
 
     let mut k = 0;
     let a = std::iter::from_fn(move || {
@@ -113,16 +113,3 @@ fn points_on_line(
 
     a.chain(b)
 }
-
-fn euclid_gcd(a: i64, b: i64) -> i64 {
-    let mut r1 = a;
-    let mut r2 = b;
-
-    while r2 != 0 {
-        let new_rem = r1.rem_euclid(r2);
-        r1 = r2;
-        r2 = new_rem;
-    }
-
-    r1
-}
@@ -0,0 +1,295 @@
+/// Reading binary data bit by bit
+use crate::*;
+
+/// Decodes a string of hexadecimal digits into bytes, two digits per byte. Surrounding whitespace
+/// is ignored.
+pub fn decode_hex(hex: &str) -> Result<Vec<u8>> {
+    let trimmed = hex.trim();
+    let offset = hex.find(trimmed).unwrap_or(0);
+
+    let digits = trimmed
+        .char_indices()
+        .map(|(i, c)| {
+            c.to_digit(16).map(|d| d as u8).ok_or_else(|| {
+                ParseError::at_offset(hex, offset + i, "a hexadecimal digit", format!("{:?}", c))
+            })
+        })
+        .collect::<Result<Vec<u8>, _>>()?;
+
+    if digits.len() % 2 != 0 {
+        return Err(eyre!(
+            "expected an even number of hexadecimal digits, found {}",
+            digits.len()
+        ));
+    }
+
+    Ok(digits
+        .chunks(2)
+        .map(|pair| pair[0] << 4 | pair[1])
+        .collect())
+}
+
+/// Reads bits from a byte slice, most significant bit first, keeping track of the bit offset so
+/// that errors can point out where reading failed.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let bytes = decode_hex("D2FE28").unwrap();
+/// let mut reader = BitReader::new(&bytes);
+///
+/// assert_eq!(reader.read_bits(3).unwrap(), 6);
+/// assert_eq!(reader.read_bits(3).unwrap(), 4);
+/// assert!(reader.read_bool().unwrap());
+/// assert_eq!(reader.position(), 7);
+///
+/// let mut group = reader.sub_reader(4).unwrap();
+/// assert_eq!(group.read_bits(4).unwrap(), 0b0111);
+/// assert_eq!(reader.position(), 11);
+///
+/// let err = group.read_bool().unwrap_err();
+/// assert_eq!(err.to_string(), "expected 1 bit at bit offset 11, but only 0 bits remain");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct BitReader<'a> {
+    data: &'a [u8],
+    /// The bit offset of the next bit to read
+    pos: usize,
+    /// The bit offset after the last bit this reader may read
+    end: usize,
+}
+
+impl<'a> BitReader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        BitReader {
+            data,
+            pos: 0,
+            end: data.len() * 8,
+        }
+    }
+
+    /// The offset of the next bit, counted from the start of the underlying data (also for
+    /// readers created with [`BitReader::sub_reader`]).
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    /// The number of bits left to read.
+    pub fn remaining(&self) -> usize {
+        self.end - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    fn ensure(&self, n: usize) -> Result<()> {
+        if n > self.remaining() {
+            return Err(eyre!(
+                "expected {} bit{} at bit offset {}, but only {} bits remain",
+                n,
+                if n == 1 { "" } else { "s" },
+                self.pos,
+                self.remaining()
+            ));
+        }
+
+        Ok(())
+    }
+
+    /// Reads `n` bits (at most 64) as an unsigned number, most significant bit first.
+    pub fn read_bits(&mut self, n: usize) -> Result<u64> {
+        if n > 64 {
+            return Err(eyre!("cannot read {} bits into a u64", n));
+        }
+        self.ensure(n)?;
+
+        let mut value = 0;
+        for _ in 0..n {
+            let bit = self.data[self.pos / 8] >> (7 - self.pos % 8) & 1;
+            value = value << 1 | bit as u64;
+            self.pos += 1;
+        }
+
+        Ok(value)
+    }
+
+    pub fn read_bool(&mut self) -> Result<bool> {
+        Ok(self.read_bits(1)? == 1)
+    }
+
+    pub fn skip(&mut self, n: usize) -> Result<()> {
+        self.ensure(n)?;
+        self.pos += n;
+
+        Ok(())
+    }
+
+    /// Splits off the next `len` bits into a separate reader, and skips over them in this one.
+    pub fn sub_reader(&mut self, len: usize) -> Result<BitReader<'a>> {
+        self.ensure(len)?;
+
+        let sub_reader = BitReader {
+            data: self.data,
+            pos: self.pos,
+            end: self.pos + len,
+        };
+        self.pos += len;
+
+        Ok(sub_reader)
+    }
+
+    /// Whether all remaining bits are zero, as is the case for padding at the end of a
+    /// transmission.
+    pub fn only_zeros_remain(&self) -> bool {
+        let mut rest = *self;
+        while !rest.is_empty() {
+            let n = rest.remaining().min(64);
+            if rest.read_bits(n).unwrap() != 0 {
+                return false;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, PartialEq)]
+    enum Packet {
+        Literal {
+            version: u64,
+            value: u64,
+        },
+        Operator {
+            version: u64,
+            type_id: u64,
+            packets: Vec<Packet>,
+        },
+    }
+
+    fn decode_packet(reader: &mut BitReader) -> Result<Packet> {
+        let start = reader.position();
+        decode_packet_contents(reader)
+            .wrap_err_with(|| format!("while decoding the packet at bit offset {}", start))
+    }
+
+    fn decode_packet_contents(reader: &mut BitReader) -> Result<Packet> {
+        let version = reader.read_bits(3)?;
+        let type_id = reader.read_bits(3)?;
+        if type_id == 4 {
+            let mut value = 0;
+            loop {
+                let more = reader.read_bool()?;
+                value = value << 4 | reader.read_bits(4)?;
+                if !more {
+                    return Ok(Packet::Literal { version, value });
+                }
+            }
+        }
+
+        let mut packets = vec![];
+        if reader.read_bool()? {
+            for _ in 0..reader.read_bits(11)? {
+                packets.push(decode_packet(reader)?);
+            }
+        } else {
+            let len = reader.read_bits(15)? as usize;
+            let mut sub_packets = reader.sub_reader(len)?;
+            while !sub_packets.is_empty() {
+                packets.push(decode_packet(&mut sub_packets)?);
+            }
+        }
+
+        Ok(Packet::Operator {
+            version,
+            type_id,
+            packets,
+        })
+    }
+
+    fn decode_transmission(hex: &str) -> Result<Packet> {
+        let bytes = decode_hex(hex)?;
+        let mut reader = BitReader::new(&bytes);
+        let packet = decode_packet(&mut reader)?;
+        if !reader.only_zeros_remain() {
+            return Err(eyre!("unexpected data at bit offset {}", reader.position()));
+        }
+
+        Ok(packet)
+    }
+
+    fn sum_versions(packet: &Packet) -> u64 {
+        match packet {
+            Packet::Literal { version, .. } => *version,
+            Packet::Operator {
+                version, packets, ..
+            } => version + packets.iter().map(sum_versions).sum::<u64>(),
+        }
+    }
+
+    #[test]
+    pub fn test_decode_packets() {
+        assert_eq!(
+            decode_transmission("D2FE28").unwrap(),
+            Packet::Literal {
+                version: 6,
+                value: 2021
+            }
+        );
+
+        let packet = decode_transmission("38006F45291200").unwrap();
+        let Packet::Operator { packets, .. } = packet else {
+            panic!("expected an operator packet");
+        };
+        assert_eq!(
+            packets,
+            [
+                Packet::Literal {
+                    version: 6,
+                    value: 10
+                },
+                Packet::Literal {
+                    version: 2,
+                    value: 20
+                }
+            ]
+        );
+
+        for (hex, version_sum) in [
+            ("8A004A801A8002F478", 16),
+            ("620080001611562C8802118E34", 12),
+            ("C0015000016115A2E0802F182340", 23),
+            ("A0016C880162017C3686B18A3D4780", 31),
+        ] {
+            assert_eq!(
+                sum_versions(&decode_transmission(hex).unwrap()),
+                version_sum
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_malformed_packet_reports_offset() {
+        // The sub-packets are said to be 27 bits long, but the transmission ends early
+        let err = decode_transmission("38006F452912").unwrap_err();
+        let message = format!("{:?}", err);
+
+        assert!(message.contains("while decoding the packet at bit offset 0"));
+        assert!(message.contains("expected 27 bits at bit offset 22, but only 26 bits remain"));
+    }
+
+    #[test]
+    pub fn test_decode_hex() {
+        assert_eq!(decode_hex(" 0aFf\n").unwrap(), vec![0x0a, 0xff]);
+        assert!(decode_hex("abc").is_err());
+
+        let err = decode_hex("12x4").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.column, 3);
+    }
+}
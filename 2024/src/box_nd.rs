@@ -0,0 +1,392 @@
+/// Axis-aligned boxes in D dimensions and sets of them
+use crate::*;
+
+/// An axis-aligned box of integer points, `min[axis] <= p[axis] < max[axis]` along every axis.
+/// Boxes with `max[axis] <= min[axis]` along any axis are empty.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let a = BoxN::inclusive([10, 10, 10], [12, 12, 12]);
+/// let b = BoxN::inclusive([11, 11, 11], [13, 13, 13]);
+///
+/// assert_eq!(a.volume(), 27);
+/// assert_eq!(a.intersection(&b), BoxN::inclusive([11, 11, 11], [12, 12, 12]));
+/// assert_eq!(a.difference(&b).iter().map(|p| p.volume()).sum::<i128>(), 19);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct BoxN<const D: usize> {
+    pub min: [i64; D],
+    pub max: [i64; D],
+}
+
+impl<const D: usize> BoxN<D> {
+    pub fn new(min: [i64; D], max: [i64; D]) -> Self {
+        BoxN { min, max }
+    }
+
+    /// The box from `first` up to and including `last` along every axis.
+    pub fn inclusive(first: [i64; D], last: [i64; D]) -> Self {
+        BoxN {
+            min: first,
+            max: last.map(|v| v + 1),
+        }
+    }
+
+    pub fn empty() -> Self {
+        BoxN {
+            min: [0; D],
+            max: [0; D],
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        (0..D).any(|axis| self.max[axis] <= self.min[axis])
+    }
+
+    /// The extent of the box along `axis` as an interval.
+    pub fn axis(&self, axis: usize) -> Interval<i64> {
+        Interval::new(self.min[axis], self.max[axis])
+    }
+
+    /// The number of integer points in the box.
+    pub fn volume(&self) -> i128 {
+        if self.is_empty() {
+            return 0;
+        }
+
+        (0..D)
+            .map(|axis| (self.max[axis] - self.min[axis]) as i128)
+            .product()
+    }
+
+    pub fn contains(&self, point: [i64; D]) -> bool {
+        (0..D).all(|axis| self.min[axis] <= point[axis] && point[axis] < self.max[axis])
+    }
+
+    pub fn contains_box(&self, other: &Self) -> bool {
+        other.is_empty()
+            || (0..D)
+                .all(|axis| self.min[axis] <= other.min[axis] && other.max[axis] <= self.max[axis])
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The intersection of both boxes, which is empty if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = *self;
+        for axis in 0..D {
+            intersection.min[axis] = self.min[axis].max(other.min[axis]);
+            intersection.max[axis] = self.max[axis].min(other.max[axis]);
+        }
+
+        if intersection.is_empty() {
+            Self::empty()
+        } else {
+            intersection
+        }
+    }
+
+    /// The part of this box outside `other`, as at most `2 * D` disjoint boxes.
+    pub fn difference(&self, other: &Self) -> Vec<Self> {
+        if self.is_empty() {
+            return vec![];
+        }
+
+        if !self.overlaps(other) {
+            return vec![*self];
+        }
+
+        // Slice off the parts below and above `other` one axis at a time, and keep cutting
+        // what is left in the middle along the next axis.
+        let mut pieces = vec![];
+        let mut rest = *self;
+        for axis in 0..D {
+            if rest.min[axis] < other.min[axis] {
+                let mut below = rest;
+                below.max[axis] = other.min[axis];
+                pieces.push(below);
+                rest.min[axis] = other.min[axis];
+            }
+
+            if other.max[axis] < rest.max[axis] {
+                let mut above = rest;
+                above.min[axis] = other.max[axis];
+                pieces.push(above);
+                rest.max[axis] = other.max[axis];
+            }
+        }
+
+        pieces
+    }
+}
+
+/// A set of integer points, stored as disjoint boxes so that its volume can be computed exactly.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let mut reactor = BoxSet::new();
+/// reactor.insert(BoxN::inclusive([10, 10, 10], [12, 12, 12]));
+/// reactor.insert(BoxN::inclusive([11, 11, 11], [13, 13, 13]));
+/// reactor.remove(BoxN::inclusive([9, 9, 9], [11, 11, 11]));
+/// reactor.insert(BoxN::inclusive([10, 10, 10], [10, 10, 10]));
+///
+/// assert_eq!(reactor.volume(), 39);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct BoxSet<const D: usize> {
+    boxes: Vec<BoxN<D>>,
+}
+
+impl<const D: usize> BoxSet<D> {
+    pub fn new() -> Self {
+        BoxSet { boxes: vec![] }
+    }
+
+    /// The disjoint boxes that make up the set.
+    pub fn boxes(&self) -> &[BoxN<D>] {
+        &self.boxes
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.boxes.is_empty()
+    }
+
+    /// The number of integer points in the set.
+    pub fn volume(&self) -> i128 {
+        self.boxes.iter().map(|b| b.volume()).sum()
+    }
+
+    pub fn contains(&self, point: [i64; D]) -> bool {
+        self.boxes.iter().any(|b| b.contains(point))
+    }
+
+    pub fn insert(&mut self, new: BoxN<D>) {
+        if new.is_empty() {
+            return;
+        }
+
+        // Only add the parts of the new box that are not covered yet
+        let mut pieces = vec![new];
+        for existing in &self.boxes {
+            if pieces.is_empty() {
+                return;
+            }
+
+            pieces = pieces
+                .iter()
+                .flat_map(|piece| piece.difference(existing))
+                .collect();
+        }
+
+        self.boxes.extend(pieces);
+    }
+
+    pub fn remove(&mut self, removed: BoxN<D>) {
+        if removed.is_empty() {
+            return;
+        }
+
+        self.boxes = self
+            .boxes
+            .iter()
+            .flat_map(|b| b.difference(&removed))
+            .collect();
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &b in &other.boxes {
+            union.insert(b);
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let boxes = self
+            .boxes
+            .iter()
+            .cartesian_product(&other.boxes)
+            .map(|(a, b)| a.intersection(b))
+            .filter(|b| !b.is_empty())
+            .collect();
+
+        BoxSet { boxes }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for &b in &other.boxes {
+            difference.remove(b);
+        }
+
+        difference
+    }
+}
+
+impl<const D: usize> FromIterator<BoxN<D>> for BoxSet<D> {
+    fn from_iter<I: IntoIterator<Item = BoxN<D>>>(iter: I) -> Self {
+        let mut set = BoxSet::new();
+        for b in iter {
+            set.insert(b);
+        }
+
+        set
+    }
+}
+
+impl<const D: usize> From<BoxN<D>> for BoxSet<D> {
+    fn from(b: BoxN<D>) -> Self {
+        BoxSet::from_iter([b])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random;
+
+    const REBOOT_STEPS: &str = "\
+on x=-5..47,y=-31..22,z=-19..33
+on x=-44..5,y=-27..21,z=-14..35
+on x=-49..-1,y=-11..42,z=-10..38
+on x=-20..34,y=-40..6,z=-44..1
+off x=26..39,y=40..50,z=-2..11
+on x=-41..5,y=-41..6,z=-36..8
+off x=-43..-33,y=-45..-28,z=7..25
+on x=-33..15,y=-32..19,z=-34..11
+off x=35..47,y=-46..-34,z=-11..5
+on x=-14..36,y=-6..44,z=-16..29
+on x=-57795..-6158,y=29564..72030,z=20435..90618
+on x=36731..105352,y=-21140..28532,z=16094..90401
+on x=30999..107136,y=-53464..15513,z=8553..71215
+on x=13528..83982,y=-99403..-27377,z=-24141..23996
+on x=-72682..-12347,y=18159..111354,z=7391..80950
+on x=-1060..80757,y=-65301..-20884,z=-103788..-16709
+on x=-83015..-9461,y=-72160..-8347,z=-81239..-26856
+on x=-52752..22273,y=-49450..9096,z=54442..119054
+on x=-29982..40483,y=-108474..-28371,z=-24328..38471
+on x=-4958..62750,y=40422..118853,z=-7672..65583
+on x=55694..108686,y=-43367..46958,z=-26781..48729
+on x=-98497..-18186,y=-63569..3412,z=1232..88485
+on x=-726..56291,y=-62629..13224,z=18033..85226
+on x=-110886..-34664,y=-81338..-8658,z=8914..63723
+on x=-55829..24974,y=-16897..54165,z=-121762..-28058
+on x=-65152..-11147,y=22489..91432,z=-58782..1780
+on x=-120100..-32970,y=-46592..27473,z=-11695..61039
+on x=-18631..37533,y=-124565..-50804,z=-35667..28308
+on x=-57817..18248,y=49321..117703,z=5745..55881
+on x=14781..98692,y=-1341..70827,z=15753..70151
+on x=-34419..55919,y=-19626..40991,z=39015..114138
+on x=-60785..11593,y=-56135..2999,z=-95368..-26915
+on x=-32178..58085,y=17647..101866,z=-91405..-8878
+on x=-53655..12091,y=50097..105568,z=-75335..-4862
+on x=-111166..-40997,y=-71714..2688,z=5609..50954
+on x=-16602..70118,y=-98693..-44401,z=5197..76897
+on x=16383..101554,y=4615..83635,z=-44907..18747
+off x=-95822..-15171,y=-19987..48940,z=10804..104439
+on x=-89813..-14614,y=16069..88491,z=-3297..45228
+on x=41075..99376,y=-20427..49978,z=-52012..13762
+on x=-21330..50085,y=-17944..62733,z=-112280..-30197
+on x=-16478..35915,y=36008..118594,z=-7885..47086
+off x=-98156..-27851,y=-49952..43171,z=-99005..-8456
+off x=2032..69770,y=-71013..4824,z=7471..94418
+on x=43670..120875,y=-42068..12382,z=-24787..38892
+off x=37514..111226,y=-45862..25743,z=-16714..54663
+off x=25699..97951,y=-30668..59918,z=-15349..69697
+off x=-44271..17935,y=-9516..60759,z=49131..112598
+on x=-61695..-5813,y=40978..94975,z=8655..80240
+off x=-101086..-9439,y=-7088..67543,z=33935..83858
+off x=18020..114017,y=-48931..32606,z=21474..89843
+off x=-77139..10506,y=-89994..-18797,z=-80..59318
+off x=8476..79288,y=-75520..11602,z=-96624..-24783
+on x=-47488..-1262,y=24338..100707,z=16292..72967
+off x=-84341..13987,y=2429..92914,z=-90671..-1318
+off x=-37810..49457,y=-71013..-7894,z=-105357..-13188
+off x=-27365..46395,y=31009..98017,z=15428..76570
+off x=-70369..-16548,y=22648..78696,z=-1892..86821
+on x=-53470..21291,y=-120233..-33476,z=-44150..38147
+off x=-93533..-4276,y=-16170..68771,z=-104985..-24507
+";
+
+    fn reboot(steps: &str) -> BoxSet<3> {
+        let mut reactor = BoxSet::new();
+        for line in steps.lines() {
+            let [x0, x1, y0, y1, z0, z1] = parse_n(line).unwrap();
+            let cuboid = BoxN::inclusive([x0, y0, z0], [x1, y1, z1]);
+            if line.starts_with("on") {
+                reactor.insert(cuboid);
+            } else {
+                reactor.remove(cuboid);
+            }
+        }
+
+        reactor
+    }
+
+    #[test]
+    pub fn test_reactor_reboot() {
+        let reactor = reboot(REBOOT_STEPS);
+
+        assert_eq!(reactor.volume(), 2758514936282235);
+
+        let initialization_area = BoxSet::from(BoxN::inclusive([-50; 3], [50; 3]));
+        assert_eq!(reactor.intersection(&initialization_area).volume(), 474140);
+    }
+
+    fn random_box(state: &mut u64) -> BoxN<2> {
+        let mut b = BoxN::empty();
+        for axis in 0..2 {
+            b.min[axis] = (random(state) % 8) as i64;
+            b.max[axis] = (b.min[axis] + (random(state) % 5) as i64).min(8);
+        }
+
+        b
+    }
+
+    fn random_set(state: &mut u64) -> BoxSet<2> {
+        let n = random(state) % 4;
+        (0..n).map(|_| random_box(state)).collect()
+    }
+
+    /// Sets of points in an 8x8 square as bitmasks, to check against
+    fn to_model(set: &BoxSet<2>) -> u64 {
+        (0..64)
+            .filter(|&i| set.contains([i / 8, i % 8]))
+            .fold(0, |model, i| model | (1 << i))
+    }
+
+    fn is_disjoint(set: &BoxSet<2>) -> bool {
+        set.boxes().iter().all(|b| !b.is_empty())
+            && set
+                .boxes()
+                .iter()
+                .tuple_combinations()
+                .all(|(a, b)| !a.overlaps(b))
+    }
+
+    #[test]
+    pub fn test_set_operations_match_model() {
+        let mut state = 0x2021;
+        for _ in 0..2000 {
+            let (a, b) = (random_set(&mut state), random_set(&mut state));
+            let (ma, mb) = (to_model(&a), to_model(&b));
+
+            for (result, expected) in [
+                (a.union(&b), ma | mb),
+                (a.intersection(&b), ma & mb),
+                (a.difference(&b), ma & !mb),
+            ] {
+                assert!(is_disjoint(&result), "{:?}", result);
+                assert_eq!(to_model(&result), expected);
+                assert_eq!(result.volume(), expected.count_ones() as i128);
+            }
+        }
+    }
+}
@@ -0,0 +1,449 @@
+/// Branch-and-bound search for maximisation problems
+use crate::*;
+use rayon::prelude::*;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::hash::Hash;
+use std::sync::atomic::{AtomicI64, Ordering as AtomicOrdering};
+
+/// A maximisation problem over a tree of states, to be solved with [`maximize`].
+pub trait Maximize {
+    type State: Clone;
+    /// Identifies states for [`Maximize::dominance`]. Use `()` if there is no dominance.
+    type Key: Hash + Eq;
+
+    /// The states reachable in one step from `state`.
+    fn successors(&self, state: &Self::State) -> Vec<Self::State>;
+
+    /// The value of the solution represented by `state`, such as the amount of pressure released
+    /// if nothing else is done from this state on.
+    fn value(&self, state: &Self::State) -> i64;
+
+    /// An upper bound on the value of `state` and of every state reachable from it. The tighter
+    /// the bound, the more states can be pruned.
+    fn upper_bound(&self, state: &Self::State) -> i64;
+
+    /// A key and a score such that a state can be pruned if a state with the same key and a score
+    /// at least as high has already been explored. Returning the same score for every state with
+    /// a key skips duplicate states.
+    fn dominance(&self, _state: &Self::State) -> Option<(Self::Key, i64)> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOrder {
+    /// Explores the most recently found state first, which uses little memory.
+    DepthFirst,
+    /// Explores the state with the highest upper bound first, which stops as soon as no other
+    /// state can do better than the best state found.
+    BestFirst,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SearchStats {
+    /// The number of states that were explored
+    pub nodes: u64,
+    /// The number of states pruned because their upper bound was not better than the best value
+    pub pruned_by_bound: u64,
+    /// The number of states pruned because an explored state dominated them
+    pub pruned_by_dominance: u64,
+}
+
+impl SearchStats {
+    fn add(self, other: SearchStats) -> SearchStats {
+        SearchStats {
+            nodes: self.nodes + other.nodes,
+            pruned_by_bound: self.pruned_by_bound + other.pruned_by_bound,
+            pruned_by_dominance: self.pruned_by_dominance + other.pruned_by_dominance,
+        }
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct SearchResult<S> {
+    pub best_value: i64,
+    pub best_state: S,
+    pub stats: SearchStats,
+}
+
+/// Finds the state with the highest value that is reachable from `initial`.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// /// Pick digits (in order) from a number to form the largest number with `len` digits
+/// struct LargestSubsequence {
+///     digits: Vec<i64>,
+///     len: usize,
+/// }
+///
+/// impl Maximize for LargestSubsequence {
+///     /// The number formed so far, and how many digits have been considered
+///     type State = (i64, usize, usize);
+///     type Key = ();
+///
+///     fn successors(&self, &(number, picked, next): &Self::State) -> Vec<Self::State> {
+///         if picked == self.len {
+///             return vec![];
+///         }
+///         (next..self.digits.len())
+///             .map(|i| (number * 10 + self.digits[i], picked + 1, i + 1))
+///             .collect()
+///     }
+///
+///     fn value(&self, &(number, picked, _): &Self::State) -> i64 {
+///         if picked == self.len { number } else { 0 }
+///     }
+///
+///     fn upper_bound(&self, &(number, picked, _): &Self::State) -> i64 {
+///         (picked..self.len).fold(number, |bound, _| bound * 10 + 9)
+///     }
+/// }
+///
+/// let problem = LargestSubsequence { digits: vec![8, 1, 8, 1, 8, 1, 9, 1, 1, 1, 1, 2, 1, 1, 1], len: 4 };
+/// let result = maximize(&problem, (0, 0, 0), SearchOrder::DepthFirst);
+///
+/// assert_eq!(result.best_value, 9211);
+/// assert!(result.stats.pruned_by_bound > 0);
+/// ```
+pub fn maximize<P: Maximize>(
+    problem: &P,
+    initial: P::State,
+    order: SearchOrder,
+) -> SearchResult<P::State> {
+    let best = AtomicI64::new(i64::MIN);
+    search(problem, initial, order, &best)
+}
+
+/// Like [`maximize`], but searches the successors of `initial` in parallel. The best value found
+/// so far is shared between the threads for pruning, but dominance is only checked within each
+/// branch.
+pub fn maximize_parallel<P>(
+    problem: &P,
+    initial: P::State,
+    order: SearchOrder,
+) -> SearchResult<P::State>
+where
+    P: Maximize + Sync,
+    P::State: Send,
+{
+    let best = AtomicI64::new(problem.value(&initial));
+    let root = SearchResult {
+        best_value: problem.value(&initial),
+        best_state: initial.clone(),
+        stats: SearchStats {
+            nodes: 1,
+            ..SearchStats::default()
+        },
+    };
+
+    problem
+        .successors(&initial)
+        .into_par_iter()
+        .map(|state| search(problem, state, order, &best))
+        .collect::<Vec<_>>()
+        .into_iter()
+        .fold(root, |a, b| {
+            let stats = a.stats.add(b.stats);
+            let best = if b.best_value > a.best_value { b } else { a };
+            SearchResult { stats, ..best }
+        })
+}
+
+/// A state in the best-first queue, ordered by its upper bound
+struct Candidate<S> {
+    bound: i64,
+    state: S,
+}
+
+impl<S> PartialEq for Candidate<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.bound == other.bound
+    }
+}
+
+impl<S> Eq for Candidate<S> {}
+
+impl<S> PartialOrd for Candidate<S> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<S> Ord for Candidate<S> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.bound.cmp(&other.bound)
+    }
+}
+
+fn search<P: Maximize>(
+    problem: &P,
+    initial: P::State,
+    order: SearchOrder,
+    best: &AtomicI64,
+) -> SearchResult<P::State> {
+    let mut stats = SearchStats::default();
+    let mut best_value = i64::MIN;
+    let mut best_state = initial.clone();
+    let mut dominance: HashMap<P::Key, i64> = HashMap::default();
+
+    let mut stack = vec![];
+    let mut queue = BinaryHeap::new();
+    let push = |state: P::State, stack: &mut Vec<_>, queue: &mut BinaryHeap<_>| match order {
+        SearchOrder::DepthFirst => stack.push(state),
+        SearchOrder::BestFirst => queue.push(Candidate {
+            bound: problem.upper_bound(&state),
+            state,
+        }),
+    };
+    push(initial, &mut stack, &mut queue);
+
+    loop {
+        let (state, bound) = match order {
+            SearchOrder::DepthFirst => match stack.pop() {
+                Some(state) => {
+                    let bound = problem.upper_bound(&state);
+                    (state, bound)
+                }
+                None => break,
+            },
+            SearchOrder::BestFirst => match queue.pop() {
+                Some(Candidate { bound, state }) => (state, bound),
+                None => break,
+            },
+        };
+
+        if bound <= best.load(AtomicOrdering::Relaxed) {
+            stats.pruned_by_bound += 1;
+            if order == SearchOrder::BestFirst {
+                // Every other state in the queue has a bound that is at most as high
+                stats.pruned_by_bound += queue.len() as u64;
+                break;
+            }
+            continue;
+        }
+
+        if let Some((key, score)) = problem.dominance(&state) {
+            match dominance.get(&key) {
+                Some(&seen) if seen >= score => {
+                    stats.pruned_by_dominance += 1;
+                    continue;
+                }
+                _ => {
+                    dominance.insert(key, score);
+                }
+            }
+        }
+
+        stats.nodes += 1;
+        let value = problem.value(&state);
+        if value > best_value {
+            best_value = value;
+            best_state = state.clone();
+            best.fetch_max(value, AtomicOrdering::Relaxed);
+        }
+
+        for successor in problem.successors(&state) {
+            push(successor, &mut stack, &mut queue);
+        }
+    }
+
+    SearchResult {
+        best_value,
+        best_state,
+        stats,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random;
+
+    struct Blueprint {
+        /// The amount of ore, clay and obsidian it costs to build each kind of robot
+        costs: [[i64; 3]; 4],
+        minutes: i64,
+    }
+
+    /// Minutes left, robots and resources (ore, clay, obsidian and geodes)
+    type Factory = (i64, [i64; 4], [i64; 4]);
+
+    impl Maximize for Blueprint {
+        type State = Factory;
+        type Key = Factory;
+
+        fn successors(&self, &(minutes, robots, resources): &Factory) -> Vec<Factory> {
+            let mut successors = vec![];
+            for (robot, cost) in self.costs.iter().enumerate() {
+                let max_needed = self
+                    .costs
+                    .iter()
+                    .map(|c| c.get(robot).copied().unwrap_or(0));
+                if robot < 3 && robots[robot] >= max_needed.max().unwrap() {
+                    continue;
+                }
+
+                // Wait until the robot can be built, and then build it
+                let mut wait = 0;
+                for kind in 0..3 {
+                    if cost[kind] > resources[kind] {
+                        if robots[kind] == 0 {
+                            wait = i64::MAX;
+                            break;
+                        }
+                        let missing = cost[kind] - resources[kind];
+                        wait = wait.max((missing + robots[kind] - 1) / robots[kind]);
+                    }
+                }
+                if wait >= minutes - 1 {
+                    continue;
+                }
+
+                let mut next = (minutes - wait - 1, robots, resources);
+                for (kind, resource) in next.2.iter_mut().enumerate() {
+                    *resource += robots[kind] * (wait + 1) - cost.get(kind).unwrap_or(&0);
+                }
+                next.1[robot] += 1;
+                successors.push(next);
+            }
+
+            successors
+        }
+
+        fn value(&self, &(minutes, robots, resources): &Factory) -> i64 {
+            resources[3] + robots[3] * minutes
+        }
+
+        fn upper_bound(&self, state: &Factory) -> i64 {
+            // Build a geode robot every remaining minute
+            let minutes = state.0;
+            self.value(state) + minutes * (minutes - 1) / 2
+        }
+
+        fn dominance(&self, state: &Factory) -> Option<(Factory, i64)> {
+            Some((*state, 0))
+        }
+    }
+
+    #[test]
+    pub fn test_geode_factory() {
+        let blueprints = [
+            Blueprint {
+                costs: [[4, 0, 0], [2, 0, 0], [3, 14, 0], [2, 0, 7]],
+                minutes: 24,
+            },
+            Blueprint {
+                costs: [[2, 0, 0], [3, 0, 0], [3, 8, 0], [3, 0, 12]],
+                minutes: 24,
+            },
+        ];
+
+        for (blueprint, geodes) in blueprints.iter().zip([9, 12]) {
+            let initial = (blueprint.minutes, [1, 0, 0, 0], [0; 4]);
+            for order in [SearchOrder::DepthFirst, SearchOrder::BestFirst] {
+                assert_eq!(maximize(blueprint, initial, order).best_value, geodes);
+                assert_eq!(
+                    maximize_parallel(blueprint, initial, order).best_value,
+                    geodes
+                );
+            }
+        }
+    }
+
+    struct Knapsack {
+        /// Weight and value of each item, sorted by decreasing value per weight
+        items: Vec<(i64, i64)>,
+        capacity: i64,
+    }
+
+    impl Maximize for Knapsack {
+        /// The number of items decided on, and the total weight and value of the chosen ones
+        type State = (usize, i64, i64);
+        type Key = (usize, i64);
+
+        fn successors(&self, &(decided, weight, value): &Self::State) -> Vec<Self::State> {
+            let Some(&(item_weight, item_value)) = self.items.get(decided) else {
+                return vec![];
+            };
+
+            let mut successors = vec![(decided + 1, weight, value)];
+            if weight + item_weight <= self.capacity {
+                successors.push((decided + 1, weight + item_weight, value + item_value));
+            }
+
+            successors
+        }
+
+        fn value(&self, &(_, _, value): &Self::State) -> i64 {
+            value
+        }
+
+        fn upper_bound(&self, &(decided, weight, value): &Self::State) -> i64 {
+            // Fill up the remaining capacity, taking a fraction of the last item that fits
+            let mut bound = value;
+            let mut capacity = self.capacity - weight;
+            for &(item_weight, item_value) in &self.items[decided..] {
+                if item_weight > capacity {
+                    bound += capacity * item_value / item_weight;
+                    break;
+                }
+
+                bound += item_value;
+                capacity -= item_weight;
+            }
+
+            bound
+        }
+
+        fn dominance(
+            &self,
+            &(decided, weight, value): &Self::State,
+        ) -> Option<((usize, i64), i64)> {
+            Some(((decided, weight), value))
+        }
+    }
+
+    #[test]
+    pub fn test_knapsack_matches_brute_force() {
+        let mut rng = 0xb0b;
+        for _ in 0..50 {
+            let n = 1 + random(&mut rng) as usize % 12;
+            let mut items = (0..n)
+                .map(|_| {
+                    (
+                        1 + (random(&mut rng) % 20) as i64,
+                        (random(&mut rng) % 30) as i64,
+                    )
+                })
+                .collect_vec();
+            items.sort_by(|a, b| (b.1 * a.0).cmp(&(a.1 * b.0)));
+            let capacity = (random(&mut rng) % 60) as i64;
+
+            let brute_force = (0..1u32 << n)
+                .map(|chosen| {
+                    let chosen = items
+                        .iter()
+                        .enumerate()
+                        .filter(|(i, _)| chosen & (1 << i) != 0);
+                    chosen.fold((0, 0), |(w, v), (_, item)| (w + item.0, v + item.1))
+                })
+                .filter(|&(weight, _)| weight <= capacity)
+                .map(|(_, value)| value)
+                .max()
+                .unwrap();
+
+            let knapsack = Knapsack { items, capacity };
+            for order in [SearchOrder::DepthFirst, SearchOrder::BestFirst] {
+                let result = maximize(&knapsack, (0, 0, 0), order);
+                assert_eq!(result.best_value, brute_force);
+                assert_eq!(knapsack.value(&result.best_state), brute_force);
+
+                let result = maximize_parallel(&knapsack, (0, 0, 0), order);
+                assert_eq!(result.best_value, brute_force);
+            }
+        }
+    }
+}
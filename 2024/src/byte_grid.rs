@@ -0,0 +1,289 @@
+/// A zero-copy 2D grid of ASCII bytes
+use crate::*;
+use std::fmt::Formatter;
+use std::ops::Index;
+
+/// A read-only 2D grid that borrows the puzzle input directly. Rather than copying the cells into
+/// a new buffer, the line endings are skipped over by treating each line (including its line
+/// ending) as the stride between rows.
+///
+/// Use [`ByteGrid::to_array`] to get an owned, mutable copy.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let grid = ByteGrid::from_text("#..\r\n.#.\r\n").unwrap();
+///
+/// assert_eq!(grid.shape(), (2, 3));
+/// assert_eq!(grid[(1, 1)], b'#');
+/// assert_eq!(grid.rows().collect_vec(), vec![b"#..", b".#."]);
+/// ```
+#[derive(Clone, Copy)]
+pub struct ByteGrid<'a> {
+    data: &'a [u8],
+    shape: (i64, i64),
+    stride: i64,
+}
+
+impl<'a> ByteGrid<'a> {
+    pub fn from_text(input: &'a str) -> Result<ByteGrid<'a>> {
+        Self::from_bytes(input.as_bytes())
+    }
+
+    pub fn from_bytes(input: &'a [u8]) -> Result<ByteGrid<'a>> {
+        let error = |offset: usize, expected: &str, found: &str| {
+            let text = String::from_utf8_lossy(input);
+            ParseError::at_offset(&text, offset, expected, found)
+        };
+
+        let ncols = input
+            .iter()
+            .position(|&b| b == b'\n' || b == b'\r')
+            .unwrap_or(input.len());
+        if ncols == 0 {
+            return Err(eyre!("empty input"));
+        }
+
+        let line_ending: &[u8] = if input[ncols..].starts_with(b"\r\n") {
+            b"\r\n"
+        } else {
+            b"\n"
+        };
+        let stride = ncols + line_ending.len();
+
+        // The last row may or may not be followed by line endings, and trailing whitespace is
+        // ignored the same way as by the other text grids
+        let mut end = input.len();
+        loop {
+            while end > 0 && (input[end - 1] == b'\n' || input[end - 1] == b'\r') {
+                end -= 1;
+            }
+
+            let last_start = input[..end]
+                .iter()
+                .rposition(|&b| b == b'\n')
+                .map_or(0, |i| i + 1);
+            if last_start == 0 || !is_trailing_whitespace(&input[last_start..end], ncols) {
+                break;
+            }
+            end = last_start;
+        }
+
+        let nrows = end.div_ceil(stride);
+        for row in 0..nrows {
+            let start = row * stride;
+            let cells = &input[start..(start + ncols).min(end)];
+            if let Some(col) = cells.iter().position(|&b| b == b'\n' || b == b'\r') {
+                return Err(error(
+                    start + col,
+                    &format!("{} columns", ncols),
+                    &format!("{} columns", col),
+                )
+                .into());
+            }
+            if cells.len() < ncols {
+                return Err(error(
+                    end,
+                    &format!("{} columns", ncols),
+                    &format!("{} columns", cells.len()),
+                )
+                .into());
+            }
+
+            let terminator = &input[(start + ncols).min(end)..(start + stride).min(end)];
+            if row + 1 < nrows && terminator != line_ending {
+                return Err(error(
+                    start + ncols,
+                    "end of line",
+                    &format!("{:?}", String::from_utf8_lossy(terminator)),
+                )
+                .into());
+            }
+        }
+
+        Ok(ByteGrid {
+            data: &input[..end],
+            shape: (nrows as i64, ncols as i64),
+            stride: stride as i64,
+        })
+    }
+
+    pub fn nrows(&self) -> i64 {
+        self.shape.0
+    }
+
+    pub fn ncols(&self) -> i64 {
+        self.shape.1
+    }
+
+    pub fn shape(&self) -> (i64, i64) {
+        self.shape
+    }
+
+    pub fn in_bounds(&self, idx: Idx) -> bool {
+        idx.0 >= 0 && idx.0 < self.shape.0 && idx.1 >= 0 && idx.1 < self.shape.1
+    }
+
+    pub fn at(&self, idx: Idx) -> Option<u8> {
+        if self.in_bounds(idx) {
+            Some(self[idx])
+        } else {
+            None
+        }
+    }
+
+    pub fn row(&self, row: i64) -> &'a [u8] {
+        let start = (row * self.stride) as usize;
+        &self.data[start..start + self.shape.1 as usize]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [u8]> + '_ {
+        (0..self.shape.0).map(|row| self.row(row))
+    }
+
+    pub fn col(&self, col: i64) -> impl Iterator<Item = u8> + '_ {
+        (0..self.shape.0).map(move |row| self[(row, col)])
+    }
+
+    pub fn cols(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        (0..self.shape.1).map(|col| self.col(col).collect())
+    }
+
+    pub fn indices(&self) -> Array2DIndicesIter {
+        Array2DIndicesIter::row_major(self.shape)
+    }
+
+    pub fn indexed_iter(&self) -> impl Iterator<Item = (Idx, u8)> + '_ {
+        self.indices().map(|idx| (idx, self[idx]))
+    }
+
+    pub fn step_from(&self, init: Idx, step: Idx) -> impl Iterator<Item = u8> + '_ {
+        let mut current = init;
+
+        std::iter::from_fn(move || {
+            let value = self.at(current)?;
+            current.0 += step.0;
+            current.1 += step.1;
+
+            Some(value)
+        })
+    }
+
+    pub fn von_neumann_neighborhood(&self, pos: Idx) -> impl Iterator<Item = u8> + '_ {
+        self.indexed_von_neumann_neighborhood(pos)
+            .map(|(_pos, value)| value)
+    }
+
+    pub fn indexed_von_neumann_neighborhood(
+        &self,
+        pos: Idx,
+    ) -> impl Iterator<Item = (Idx, u8)> + '_ {
+        self.relative(pos, &VON_NEUMANN_NEIGHBORHOOD)
+    }
+
+    pub fn moore_neighborhood(&self, pos: Idx) -> impl Iterator<Item = u8> + '_ {
+        self.indexed_moore_neighborhood(pos)
+            .map(|(_pos, value)| value)
+    }
+
+    pub fn indexed_moore_neighborhood(&self, pos: Idx) -> impl Iterator<Item = (Idx, u8)> + '_ {
+        self.relative(pos, &MOORE_NEIGHBORHOOD)
+    }
+
+    fn relative(&self, pos: Idx, offsets: &'static [Idx]) -> impl Iterator<Item = (Idx, u8)> + '_ {
+        offsets.iter().filter_map(move |offset| {
+            let next_pos = (pos.0 + offset.0, pos.1 + offset.1);
+            self.at(next_pos).map(|value| (next_pos, value))
+        })
+    }
+
+    /// Copies the grid into an owned array.
+    pub fn to_array(&self) -> Array2D<u8> {
+        let data = self.rows().flatten().copied().collect();
+
+        Array2D::from_shape_vec(self.shape, data)
+    }
+
+    pub fn to_char_grid(&self) -> CharGrid {
+        let data = self.rows().flatten().map(|&b| b as char).collect();
+
+        Array2D::from_shape_vec(self.shape, data)
+    }
+}
+
+impl Index<Idx> for ByteGrid<'_> {
+    type Output = u8;
+
+    fn index(&self, index: Idx) -> &Self::Output {
+        if !self.in_bounds(index) {
+            panic!("index {:?} out of bounds for shape {:?}", index, self.shape);
+        }
+
+        &self.data[(index.0 * self.stride + index.1) as usize]
+    }
+}
+
+impl Debug for ByteGrid<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for row in self.rows() {
+            writeln!(f, "{}", String::from_utf8_lossy(row))?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_same_as_char_grid() {
+        let text = "#.#.\n..#.\n###.";
+
+        let bytes = ByteGrid::from_text(text).unwrap();
+        let chars = CharGrid::from_text(text).unwrap();
+
+        assert_eq!(bytes.to_char_grid(), chars);
+        assert_eq!(
+            bytes.indexed_moore_neighborhood((1, 0)).collect_vec(),
+            chars
+                .indexed_moore_neighborhood((1, 0))
+                .map(|(pos, c)| (pos, c as u8))
+                .collect_vec()
+        );
+        assert_eq!(bytes.cols().nth(1).unwrap(), b"..#");
+    }
+
+    #[test]
+    pub fn test_line_endings() {
+        for text in ["ab\ncd", "ab\ncd\n", "ab\r\ncd\r\n", "ab\ncd\n\n"] {
+            let grid = ByteGrid::from_text(text).unwrap();
+
+            assert_eq!(grid.shape(), (2, 2), "{:?}", text);
+            assert_eq!(grid.rows().collect_vec(), vec![b"ab", b"cd"]);
+        }
+    }
+
+    #[test]
+    pub fn test_trailing_whitespace_same_as_char_grid() {
+        for text in ["ab\ncd\n    ", "ab\r\ncd\r\n \r\n", "  \n  \n"] {
+            let bytes = ByteGrid::from_text(text).unwrap();
+            let chars = CharGrid::from_text(text).unwrap();
+
+            assert_eq!(bytes.to_char_grid(), chars, "{:?}", text);
+        }
+    }
+
+    #[test]
+    pub fn test_rejects_ragged_rows() {
+        for text in ["abc\nab\nabc\n", "abc\nabcd\nabc", "abc\nabc\nab"] {
+            assert!(ByteGrid::from_text(text).is_err(), "{:?}", text);
+        }
+
+        let err = ByteGrid::from_text("abc\nab\nabc\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line_no, err.column), (Some(2), 3));
+    }
+}
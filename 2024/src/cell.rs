@@ -0,0 +1,183 @@
+/// Parsing grids of characters into grids of user-defined cell types
+use crate::*;
+
+/// A type that a single character of a text grid can be parsed into.
+///
+/// Usually implemented with the [`cell_enum!`](crate::cell_enum) macro.
+pub trait FromCell: Sized {
+    fn from_cell(c: char) -> Option<Self>;
+}
+
+impl FromCell for char {
+    fn from_cell(c: char) -> Option<Self> {
+        Some(c)
+    }
+}
+
+impl FromCell for u8 {
+    fn from_cell(c: char) -> Option<Self> {
+        c.is_ascii().then_some(c as u8)
+    }
+}
+
+impl FromCell for i64 {
+    fn from_cell(c: char) -> Option<Self> {
+        c.to_digit(10).map(|d| d as i64)
+    }
+}
+
+/// Positions of marker characters (such as `S`, `E` or `^`) seen while parsing a grid.
+#[derive(Debug, Default, Clone)]
+pub struct Markers {
+    positions: HashMap<char, Vec<Idx>>,
+}
+
+impl Markers {
+    /// All positions of `marker`, in row-major order.
+    pub fn all(&self, marker: char) -> &[Idx] {
+        self.positions.get(&marker).map_or(&[], |p| p.as_slice())
+    }
+
+    /// The position of `marker`, which must occur exactly once.
+    pub fn find(&self, marker: char) -> Result<Idx> {
+        match self.all(marker) {
+            [pos] => Ok(*pos),
+            [] => Err(eyre!("marker {:?} not found", marker)),
+            positions => Err(eyre!(
+                "marker {:?} found {} times, expected once",
+                marker,
+                positions.len()
+            )),
+        }
+    }
+}
+
+impl<T: FromCell> Array2D<T> {
+    /// Parses a text grid, mapping every character to a `T`.
+    ///
+    /// Example:
+    /// ```
+    /// use aoc2024::*;
+    ///
+    /// cell_enum! {
+    ///     #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    ///     enum Tile {
+    ///         '#' => Wall,
+    ///         '.' | 'S' => Open,
+    ///     }
+    /// }
+    ///
+    /// let (grid, markers) = Array2D::<Tile>::parse_with_markers("#S\n.#", "S").unwrap();
+    ///
+    /// assert_eq!(grid[(0, 0)], Tile::Wall);
+    /// assert_eq!(grid[(0, 1)], Tile::Open);
+    /// assert_eq!(markers.find('S').unwrap(), (0, 1));
+    /// ```
+    pub fn parse(text: &str) -> Result<Self> {
+        Self::parse_with_markers(text, "").map(|(grid, _markers)| grid)
+    }
+
+    /// Like [`Array2D::parse`], but also records the positions of every character in `markers`.
+    pub fn parse_with_markers(text: &str, markers: &str) -> Result<(Self, Markers)> {
+        let lines = grid_lines(text)?;
+        let nrows = lines.len() as i64;
+        let ncols = lines[0].1.chars().count() as i64;
+
+        let mut found = Markers::default();
+        let mut data = Vec::with_capacity((nrows * ncols) as usize);
+        for (row, (_, line)) in lines.iter().enumerate() {
+            for (col, c) in line.chars().enumerate() {
+                let cell = T::from_cell(c).ok_or_else(|| {
+                    let type_name = std::any::type_name::<T>().rsplit("::").next().unwrap();
+                    let expected = format!("a character representing a {}", type_name);
+                    ParseError::new(line, Some(row + 1), col + 1, expected, format!("{:?}", c))
+                })?;
+                data.push(cell);
+
+                if markers.contains(c) {
+                    found
+                        .positions
+                        .entry(c)
+                        .or_default()
+                        .push((row as i64, col as i64));
+                }
+            }
+        }
+
+        Ok((Self::try_from_shape_vec((nrows, ncols), data)?, found))
+    }
+}
+
+/// Declares an enum of grid cells together with the characters they are parsed from.
+///
+/// The first character of each variant is used when displaying the cell.
+#[macro_export]
+macro_rules! cell_enum {
+    (
+        $(#[$meta:meta])*
+        $vis:vis enum $name:ident {
+            $($first:literal $(| $rest:literal)* => $variant:ident),+ $(,)?
+        }
+    ) => {
+        $(#[$meta])*
+        $vis enum $name {
+            $($variant),+
+        }
+
+        impl $crate::FromCell for $name {
+            fn from_cell(c: char) -> Option<Self> {
+                match c {
+                    $($first $(| $rest)* => Some($name::$variant),)+
+                    _ => None,
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                let c = match self {
+                    $($name::$variant => $first,)+
+                };
+
+                write!(f, "{}", c)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    cell_enum! {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+        enum Tile {
+            '#' => Wall,
+            '.' | '^' => Open,
+        }
+    }
+
+    #[test]
+    pub fn test_parse_with_markers() {
+        let text = "#.#\n.^.\n#.#\n";
+
+        let (grid, markers) = Array2D::<Tile>::parse_with_markers(text, "^").unwrap();
+
+        assert_eq!(grid.shape(), (3, 3));
+        assert_eq!(grid[(0, 0)], Tile::Wall);
+        assert_eq!(grid[(1, 1)], Tile::Open);
+        assert_eq!(markers.find('^').unwrap(), (1, 1));
+        assert!(markers.find('S').is_err());
+        assert_eq!(Tile::Open.to_string(), ".");
+    }
+
+    #[test]
+    pub fn test_parse_reports_unknown_characters() {
+        let err = Array2D::<Tile>::parse("#.\n.X").map(|_| ()).unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "expected a character representing a Tile, found 'X' at line 2, column 2\n  |\n2 | .X\n  |  ^"
+        );
+    }
+}
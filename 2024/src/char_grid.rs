@@ -1,23 +1,204 @@
 /// A 2D grid of characters
 use crate::*;
-use eyre::eyre;
 use std::fmt::Formatter;
 
 pub type CharGrid = Array2D<char>;
 
 impl CharGrid {
     pub fn from_text(raw_input: &str) -> Result<CharGrid> {
-        let cols = raw_input
-            .lines()
-            .next()
-            .map(|l| l.len())
-            .ok_or_else(|| eyre!("empty input"))?;
+        let lines = grid_lines(raw_input)?;
+        let rows = lines.len();
+        let cols = lines[0].1.chars().count();
 
-        let rows = raw_input.lines().count();
+        let data: Vec<char> = lines.iter().flat_map(|(_, line)| line.chars()).collect();
 
-        let data: Vec<char> = raw_input.replace('\n', "").chars().collect();
+        Self::try_from_shape_vec((rows as i64, cols as i64), data)
+    }
+
+    /// Finds every occurrence of `word` along any of the eight directions, returning the
+    /// position of its first character and the direction in which it continues. A
+    /// one-character word has no direction, and is reported once per cell with direction
+    /// `(0, 0)`.
+    pub fn find_word(&self, word: &str) -> Vec<(Idx, Idx)> {
+        let first = match word.chars().next() {
+            Some(first) => first,
+            None => return vec![],
+        };
+        let len = word.chars().count();
+        if len == 1 {
+            return self
+                .indices()
+                .filter(|&pos| self[pos] == first)
+                .map(|pos| (pos, (0, 0)))
+                .collect();
+        }
+
+        self.indices()
+            .filter(|&pos| self[pos] == first)
+            .flat_map(|pos| ALL_DIRECTIONS.iter().map(move |&dir| (pos, dir)))
+            .filter(|&(pos, dir)| {
+                itertools::equal(self.step_from(pos, dir).take(len), word.chars())
+            })
+            .collect()
+    }
+
+    /// Finds every position where `stencil` matches, returning the position of the top-left
+    /// corner of the stencil.
+    pub fn find_stencil(&self, stencil: &Stencil) -> Vec<Idx> {
+        let (nrows, ncols) = stencil.cells.shape();
+
+        let mut matches = vec![];
+        for row in 0..=(self.nrows() - nrows) {
+            for col in 0..=(self.ncols() - ncols) {
+                let is_match = stencil.cells.indexed_iter().all(|((r, c), cell)| {
+                    cell.is_none_or(|expected| self[(row + r, col + c)] == expected)
+                });
+
+                if is_match {
+                    matches.push((row, col));
+                }
+            }
+        }
+
+        matches
+    }
+
+    /// Finds all maximal horizontal runs of characters matching `predicate`, in row-major order.
+    pub fn tokens(&self, predicate: impl Fn(char) -> bool) -> Vec<Token> {
+        let mut tokens = vec![];
+        for (row, cells) in self.rows().enumerate() {
+            for (is_token, run) in &cells.iter().enumerate().group_by(|&(_, &c)| predicate(c)) {
+                if !is_token {
+                    continue;
+                }
+
+                let run = run.collect_vec();
+                tokens.push(Token {
+                    start: (row as i64, run[0].0 as i64),
+                    text: run.iter().map(|&(_, &c)| c).collect(),
+                });
+            }
+        }
+
+        tokens
+    }
+
+    /// The cells surrounding `token` (its Moore neighborhood), in row-major order.
+    pub fn token_neighbors(&self, token: &Token) -> Vec<(Idx, char)> {
+        let (row, start_col) = token.start;
+        let end_col = token.end().1;
+
+        let mut neighbors = vec![];
+        for r in (row - 1)..=(row + 1) {
+            for c in (start_col - 1)..=(end_col + 1) {
+                if r == row && c >= start_col && c <= end_col {
+                    continue;
+                }
+
+                if let Some(&value) = self.at((r, c)) {
+                    neighbors.push(((r, c), value));
+                }
+            }
+        }
+
+        neighbors
+    }
+}
+
+/// A horizontal run of characters in a [`CharGrid`], as found by [`CharGrid::tokens`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Token {
+    pub start: Idx,
+    pub text: String,
+}
+
+impl Token {
+    pub fn len(&self) -> i64 {
+        self.text.chars().count() as i64
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.text.is_empty()
+    }
+
+    /// Position of the last character of the token.
+    pub fn end(&self) -> Idx {
+        (self.start.0, self.start.1 + self.len() - 1)
+    }
+
+    pub fn contains(&self, pos: Idx) -> bool {
+        pos.0 == self.start.0 && pos.1 >= self.start.1 && pos.1 <= self.end().1
+    }
 
-        Ok(Self::from_shape_vec((rows as i64, cols as i64), data))
+    pub fn value<T>(&self) -> Result<T>
+    where
+        T: std::str::FromStr,
+        T::Err: std::error::Error + Send + Sync + 'static,
+    {
+        self.text
+            .parse()
+            .wrap_err_with(|| format!("while parsing token {:?} at {:?}", self.text, self.start))
+    }
+}
+
+/// All eight (row, col) directions, starting with up and going clockwise.
+pub const ALL_DIRECTIONS: [Idx; 8] = [
+    (-1, 0),
+    (-1, 1),
+    (0, 1),
+    (1, 1),
+    (1, 0),
+    (1, -1),
+    (0, -1),
+    (-1, -1),
+];
+
+/// A 2D pattern of characters with wildcards, to be searched for with [`CharGrid::find_stencil`].
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let grid = CharGrid::from_text("M.S\n.A.\nM.S").unwrap();
+/// let x_mas = Stencil::from_text("M?S\n?A?\nM?S", '?').unwrap();
+///
+/// assert_eq!(grid.find_stencil(&x_mas), vec![(0, 0)]);
+/// assert_eq!(x_mas.orientations().len(), 4);
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct Stencil {
+    cells: Array2D<Option<char>>,
+}
+
+impl Stencil {
+    /// Parses a stencil from text, where `wildcard` matches any character.
+    pub fn from_text(text: &str, wildcard: char) -> Result<Stencil> {
+        let grid = CharGrid::from_text(text)?;
+        let cells = grid
+            .data()
+            .iter()
+            .map(|&c| (c != wildcard).then_some(c))
+            .collect();
+
+        Ok(Stencil {
+            cells: Array2D::try_from_shape_vec(grid.shape(), cells)?,
+        })
+    }
+
+    /// All distinct rotations and reflections of this stencil (at most eight).
+    pub fn orientations(&self) -> Vec<Stencil> {
+        let mut orientations: Vec<Stencil> = vec![];
+        let mut cells = self.cells.clone();
+        for _ in 0..4 {
+            for candidate in [cells.clone(), cells.flipped()] {
+                if !orientations.iter().any(|o| o.cells == candidate) {
+                    orientations.push(Stencil { cells: candidate });
+                }
+            }
+            cells = cells.rotated();
+        }
+
+        orientations
     }
 }
 
@@ -34,3 +215,117 @@ impl Debug for CharGrid {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WORD_SEARCH: &str = "\
+MMMSXXMASM
+MSAMXMSMSA
+AMXSXMAAMM
+MSAMASMSMX
+XMASAMXAMM
+XXAMMXXAMA
+SMSMSASXSS
+SAXAMASAAA
+MAMMMXMMMM
+MXMXAXMASX
+";
+
+    #[test]
+    pub fn test_find_word() {
+        let grid = CharGrid::from_text(WORD_SEARCH).unwrap();
+
+        let matches = grid.find_word("XMAS");
+
+        assert_eq!(matches.len(), 18);
+        assert!(matches.contains(&((0, 5), (0, 1))));
+        assert!(matches.contains(&((4, 6), (0, -1))));
+    }
+
+    #[test]
+    pub fn test_find_single_character_word() {
+        let grid = CharGrid::from_text("XA\nAX").unwrap();
+
+        assert_eq!(
+            grid.find_word("X"),
+            vec![((0, 0), (0, 0)), ((1, 1), (0, 0))]
+        );
+    }
+
+    #[test]
+    pub fn test_find_stencil_in_all_orientations() {
+        let grid = CharGrid::from_text(WORD_SEARCH).unwrap();
+        let x_mas = Stencil::from_text("M?S\n?A?\nM?S", '?').unwrap();
+
+        let matches = x_mas
+            .orientations()
+            .iter()
+            .map(|stencil| grid.find_stencil(stencil).len())
+            .sum::<usize>();
+
+        assert_eq!(matches, 9);
+    }
+
+    #[test]
+    pub fn test_orientations_of_asymmetric_stencil() {
+        let stencil = Stencil::from_text("ab\n?c", '?').unwrap();
+
+        assert_eq!(stencil.orientations().len(), 8);
+    }
+
+    #[test]
+    pub fn test_tokens_with_neighbors() {
+        let schematic = CharGrid::from_text(
+            "\
+467..114..
+...*......
+..35..633.
+......#...
+617*......
+.....+.58.
+..592.....
+......755.
+...$.*....
+.664.598..
+",
+        )
+        .unwrap();
+
+        let numbers = schematic.tokens(|c| c.is_ascii_digit());
+        assert_eq!(numbers.len(), 10);
+        assert_eq!(numbers[1].start, (0, 5));
+        assert_eq!(numbers[1].end(), (0, 7));
+
+        let is_symbol = |c: char| c != '.' && !c.is_ascii_digit();
+        let part_numbers: u64 = numbers
+            .iter()
+            .filter(|n| {
+                schematic
+                    .token_neighbors(n)
+                    .iter()
+                    .any(|&(_, c)| is_symbol(c))
+            })
+            .map(|n| n.value::<u64>().unwrap())
+            .sum();
+        assert_eq!(part_numbers, 4361);
+
+        let mut gears: HashMap<Idx, Vec<u64>> = HashMap::default();
+        for number in &numbers {
+            for (pos, _) in schematic
+                .token_neighbors(number)
+                .into_iter()
+                .filter(|&(_, c)| c == '*')
+            {
+                gears.entry(pos).or_default().push(number.value().unwrap());
+            }
+        }
+        let gear_ratios: u64 = gears
+            .values()
+            .filter(|parts| parts.len() == 2)
+            .map(|parts| parts[0] * parts[1])
+            .sum();
+        assert_eq!(gear_ratios, 467835);
+    }
+}
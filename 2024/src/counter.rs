@@ -0,0 +1,347 @@
+/// Counting multisets
+use crate::*;
+use num_traits::PrimInt;
+use std::hash::Hash;
+use std::ops::{Add, AddAssign, BitAnd, BitOr, Sub, SubAssign};
+
+/// A multiset: a map from items to how often they occur. Only positive counts are stored, so an
+/// item whose count drops to zero disappears from the counter.
+///
+/// Instead of tracking every item separately, counts can be transformed as a whole, such as
+/// stones that each split into two:
+/// ```
+/// use aoc2024::*;
+///
+/// let mut stones: Counter<u64> = [125, 17].into_iter().frequencies();
+/// for _ in 0..6 {
+///     stones = stones.flat_map(|&stone| {
+///         let digits = stone.checked_ilog10().unwrap_or(0) + 1;
+///         match stone {
+///             0 => vec![1],
+///             _ if digits % 2 == 0 => {
+///                 let half = 10u64.pow(digits / 2);
+///                 vec![stone / half, stone % half]
+///             }
+///             _ => vec![stone * 2024],
+///         }
+///     });
+/// }
+///
+/// assert_eq!(stones.total(), 22);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Counter<T: Hash + Eq, C: PrimInt = i64> {
+    counts: HashMap<T, C>,
+}
+
+impl<T: Hash + Eq, C: PrimInt> Counter<T, C> {
+    pub fn new() -> Self {
+        Counter {
+            counts: HashMap::default(),
+        }
+    }
+
+    /// How often `item` occurs, zero if it does not.
+    pub fn count(&self, item: &T) -> C {
+        self.counts.get(item).copied().unwrap_or_else(C::zero)
+    }
+
+    /// Adds `count` occurrences of `item`. A negative count removes occurrences, down to zero.
+    pub fn insert(&mut self, item: T, count: C) {
+        let new_count = self.count(&item) + count;
+        if new_count > C::zero() {
+            self.counts.insert(item, new_count);
+        } else {
+            self.counts.remove(&item);
+        }
+    }
+
+    /// Removes up to `count` occurrences of `item`, returning how many were removed. Nothing is
+    /// removed if `count` is not positive.
+    pub fn remove(&mut self, item: &T, count: C) -> C {
+        if count <= C::zero() {
+            return C::zero();
+        }
+
+        match self.counts.get_mut(item) {
+            Some(current) if *current > count => {
+                *current = *current - count;
+                count
+            }
+            Some(_) => self.counts.remove(item).unwrap(),
+            None => C::zero(),
+        }
+    }
+
+    /// The number of distinct items.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// The number of items, counting each occurrence.
+    pub fn total(&self) -> C {
+        self.counts
+            .values()
+            .fold(C::zero(), |total, &count| total + count)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&T, C)> {
+        self.counts.iter().map(|(item, &count)| (item, count))
+    }
+
+    /// The distinct items, in no particular order.
+    pub fn items(&self) -> impl Iterator<Item = &T> {
+        self.counts.keys()
+    }
+
+    /// The `n` items with the highest counts, most common first.
+    pub fn most_common(&self, n: usize) -> Vec<(&T, C)> {
+        let mut items = self.iter().collect_vec();
+        items.sort_by_key(|&(_, count)| std::cmp::Reverse(count));
+        items.truncate(n);
+        items
+    }
+
+    /// The item with the highest count, if any.
+    pub fn max_by_count(&self) -> Option<(&T, C)> {
+        self.iter().max_by_key(|&(_, count)| count)
+    }
+
+    /// The item with the lowest count, if any.
+    pub fn min_by_count(&self) -> Option<(&T, C)> {
+        self.iter().min_by_key(|&(_, count)| count)
+    }
+
+    /// Replaces every item by the items returned by `f`, each of which inherits the count of the
+    /// item it came from. Items that are produced more than once have their counts summed.
+    pub fn flat_map<U, I>(&self, mut f: impl FnMut(&T) -> I) -> Counter<U, C>
+    where
+        U: Hash + Eq,
+        I: IntoIterator<Item = U>,
+    {
+        let mut result: Counter<U, C> = Counter::new();
+        for (item, count) in self.iter() {
+            for new_item in f(item) {
+                result.insert(new_item, count);
+            }
+        }
+
+        result
+    }
+
+    /// Replaces every item by the item returned by `f`, summing the counts of items that map to
+    /// the same result.
+    pub fn map<U: Hash + Eq>(&self, mut f: impl FnMut(&T) -> U) -> Counter<U, C> {
+        self.flat_map(|item| [f(item)])
+    }
+
+    /// The items that occur in either counter, with the highest of both counts.
+    pub fn union(mut self, other: Self) -> Self {
+        for (item, count) in other {
+            if count > self.count(&item) {
+                self.counts.insert(item, count);
+            }
+        }
+
+        self
+    }
+
+    /// The items that occur in both counters, with the lowest of both counts.
+    pub fn intersection(mut self, other: Self) -> Self {
+        self.counts.retain(|item, count| {
+            *count = (*count).min(other.count(item));
+            *count > C::zero()
+        });
+
+        self
+    }
+
+    pub fn into_map(self) -> HashMap<T, C> {
+        self.counts
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> Default for Counter<T, C> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> FromIterator<(T, C)> for Counter<T, C> {
+    fn from_iter<I: IntoIterator<Item = (T, C)>>(iter: I) -> Self {
+        let mut counter = Counter::new();
+        counter.extend(iter);
+        counter
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> Extend<(T, C)> for Counter<T, C> {
+    fn extend<I: IntoIterator<Item = (T, C)>>(&mut self, iter: I) {
+        for (item, count) in iter {
+            self.insert(item, count);
+        }
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> IntoIterator for Counter<T, C> {
+    type Item = (T, C);
+    type IntoIter = std::collections::hash_map::IntoIter<T, C>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.counts.into_iter()
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> AddAssign for Counter<T, C> {
+    fn add_assign(&mut self, other: Self) {
+        self.extend(other);
+    }
+}
+
+/// Sums the counts of both counters.
+impl<T: Hash + Eq, C: PrimInt> Add for Counter<T, C> {
+    type Output = Self;
+
+    fn add(mut self, other: Self) -> Self {
+        self += other;
+        self
+    }
+}
+
+impl<T: Hash + Eq, C: PrimInt> SubAssign for Counter<T, C> {
+    fn sub_assign(&mut self, other: Self) {
+        for (item, count) in other {
+            self.remove(&item, count);
+        }
+    }
+}
+
+/// Subtracts the counts of `other`, dropping items whose count reaches zero.
+impl<T: Hash + Eq, C: PrimInt> Sub for Counter<T, C> {
+    type Output = Self;
+
+    fn sub(mut self, other: Self) -> Self {
+        self -= other;
+        self
+    }
+}
+
+/// See [`Counter::union`].
+impl<T: Hash + Eq, C: PrimInt> BitOr for Counter<T, C> {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        self.union(other)
+    }
+}
+
+/// See [`Counter::intersection`].
+impl<T: Hash + Eq, C: PrimInt> BitAnd for Counter<T, C> {
+    type Output = Self;
+
+    fn bitand(self, other: Self) -> Self {
+        self.intersection(other)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const POLYMER_TEMPLATE: &str = "NNCB";
+
+    const PAIR_INSERTION_RULES: &str = "\
+CH -> B
+HH -> N
+CB -> H
+NH -> C
+HB -> C
+HC -> B
+HN -> C
+NN -> C
+BH -> H
+NC -> B
+NB -> B
+BN -> B
+BB -> N
+BC -> B
+CC -> N
+CN -> C
+";
+
+    /// The difference between the most and least common element after growing the polymer
+    fn grow_polymer(steps: usize) -> u64 {
+        let rules: HashMap<(char, char), char> = PAIR_INSERTION_RULES
+            .lines()
+            .map(|line| {
+                let chars = line.chars().collect_vec();
+                ((chars[0], chars[1]), chars[6])
+            })
+            .collect();
+
+        let template = POLYMER_TEMPLATE.chars().collect_vec();
+        let mut elements: Counter<char, u64> = template.iter().copied().frequencies();
+        let mut pairs: Counter<(char, char), u64> =
+            template.iter().copied().tuple_windows().frequencies();
+
+        for _ in 0..steps {
+            elements += pairs.flat_map(|pair| rules.get(pair).copied());
+            pairs = pairs.flat_map(|&(left, right)| match rules.get(&(left, right)) {
+                Some(&middle) => vec![(left, middle), (middle, right)],
+                None => vec![(left, right)],
+            });
+        }
+
+        elements.max_by_count().unwrap().1 - elements.min_by_count().unwrap().1
+    }
+
+    #[test]
+    pub fn test_polymer_growth() {
+        assert_eq!(grow_polymer(10), 1588);
+        assert_eq!(grow_polymer(40), 2188189693529);
+    }
+
+    #[test]
+    pub fn test_counts() {
+        let mut counter: Counter<char> = "abracadabra".chars().frequencies();
+
+        assert_eq!(counter.count(&'a'), 5);
+        assert_eq!(counter.count(&'z'), 0);
+        assert_eq!(counter.len(), 5);
+        assert_eq!(counter.total(), 11);
+        assert_eq!(counter.most_common(1), vec![(&'a', 5)]);
+        let counts = counter
+            .most_common(3)
+            .iter()
+            .map(|&(_, count)| count)
+            .collect_vec();
+        assert_eq!(counts, vec![5, 2, 2]);
+        assert_eq!(counter.max_by_count(), Some((&'a', 5)));
+
+        assert_eq!(counter.remove(&'a', -2), 0);
+        assert_eq!(counter.remove(&'a', 0), 0);
+        assert_eq!(counter.count(&'a'), 5);
+        assert_eq!(counter.remove(&'b', 5), 2);
+        assert_eq!(counter.count(&'b'), 0);
+        counter.insert('c', -1);
+        assert!(!counter.items().any(|&c| c == 'c'));
+        assert_eq!(counter.min_by_count(), Some((&'d', 1)));
+    }
+
+    #[test]
+    pub fn test_multiset_arithmetic() {
+        let a: Counter<char> = "aaabbc".chars().frequencies();
+        let b: Counter<char> = "abbbd".chars().frequencies();
+        let expected = |s: &str| -> Counter<char> { s.chars().frequencies() };
+
+        assert_eq!(a.clone() + b.clone(), expected("aaaabbbbbcd"));
+        assert_eq!(a.clone() - b.clone(), expected("aac"));
+        assert_eq!(b.clone() - a.clone(), expected("bd"));
+        assert_eq!(a.clone() | b.clone(), expected("aaabbbcd"));
+        assert_eq!(a & b, expected("abb"));
+    }
+}
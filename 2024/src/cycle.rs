@@ -0,0 +1,298 @@
+/// Cycle detection for simulations that repeat themselves
+use crate::*;
+use rustc_hash::FxHasher;
+use std::collections::hash_map::Entry;
+use std::hash::{BuildHasher, BuildHasherDefault, Hash};
+
+/// A cycle in a sequence of states `x0, f(x0), f(f(x0)), ...`: after `start` steps, the states
+/// repeat every `len` steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Cycle {
+    pub start: usize,
+    pub len: usize,
+}
+
+impl Cycle {
+    /// The smallest number of steps that ends up in the same state as taking `n` steps.
+    pub fn equivalent_step(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.len
+        }
+    }
+}
+
+/// The states seen until the sequence started repeating, as found by [`detect_cycle`].
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let states = detect_cycle(3u64, |&x| (x * x + 1) % 17);
+///
+/// assert_eq!(states.cycle, Cycle { start: 1, len: 6 });
+/// assert_eq!(*states.state_after(1_000_000_000), 5);
+/// ```
+#[derive(Debug, Clone)]
+pub struct StateCycle<S> {
+    pub cycle: Cycle,
+    /// The first `cycle.start + cycle.len + 1` states, starting with the initial state. The last
+    /// state repeats the state at `cycle.start`.
+    states: Vec<S>,
+}
+
+impl<S> StateCycle<S> {
+    /// `repeated` is the state after `states`, which is the same as the state at `start`.
+    fn new(start: usize, mut states: Vec<S>, repeated: S) -> Self {
+        let len = states.len() - start;
+        states.push(repeated);
+
+        StateCycle {
+            cycle: Cycle { start, len },
+            states,
+        }
+    }
+
+    pub fn states(&self) -> &[S] {
+        &self.states
+    }
+
+    pub fn state_after(&self, n: usize) -> &S {
+        &self.states[self.cycle.equivalent_step(n)]
+    }
+
+    /// Extrapolates a value derived from the states, such as a height or a score, that increases
+    /// by the same amount every time the cycle repeats.
+    pub fn extrapolate(&self, n: usize, value: impl Fn(&S) -> i64) -> i64 {
+        let Cycle { start, len } = self.cycle;
+        if n < start {
+            return value(&self.states[n]);
+        }
+
+        let per_cycle = value(&self.states[start + len]) - value(&self.states[start]);
+        let cycles = ((n - start) / len) as i64;
+
+        value(self.state_after(n)) + cycles * per_cycle
+    }
+}
+
+/// Steps from `init` until a state repeats, remembering every state.
+///
+/// Does not terminate if the states never repeat.
+pub fn detect_cycle<S: Hash + Eq>(init: S, mut step: impl FnMut(&S) -> S) -> StateCycle<S> {
+    // Every state is stored only once, in `states`. The map holds the index of the last state with
+    // a given hash, and states with the same hash are chained through `previous_with_hash`.
+    let hasher = BuildHasherDefault::<FxHasher>::default();
+    let mut last_with_hash: HashMap<u64, usize> = HashMap::default();
+    let mut previous_with_hash: Vec<Option<usize>> = vec![];
+    let mut states = vec![];
+    let mut state = init;
+    loop {
+        let hash = hasher.hash_one(&state);
+        let mut candidate = last_with_hash.get(&hash).copied();
+        while let Some(start) = candidate {
+            if states[start] == state {
+                return StateCycle::new(start, states, state);
+            }
+            candidate = previous_with_hash[start];
+        }
+
+        previous_with_hash.push(last_with_hash.insert(hash, states.len()));
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Like [`detect_cycle`], but compares states by a fingerprint computed by `key`. This is useful
+/// when states are expensive to hash, or when only part of the state determines its future.
+pub fn detect_cycle_by_key<S, K: Hash + Eq>(
+    init: S,
+    mut step: impl FnMut(&S) -> S,
+    key: impl Fn(&S) -> K,
+) -> StateCycle<S> {
+    let mut seen: HashMap<K, usize> = HashMap::default();
+    let mut states = vec![];
+    let mut state = init;
+    loop {
+        match seen.entry(key(&state)) {
+            Entry::Occupied(entry) => return StateCycle::new(*entry.get(), states, state),
+            Entry::Vacant(entry) => {
+                entry.insert(states.len());
+            }
+        }
+
+        let next = step(&state);
+        states.push(state);
+        state = next;
+    }
+}
+
+/// Finds the cycle in the states reached from `init` using Brent's algorithm, which only keeps
+/// two states in memory at a time, but calls `step` a few times more than [`detect_cycle`].
+///
+/// Does not terminate if the states never repeat.
+pub fn find_cycle<S: Clone + Eq>(init: S, mut step: impl FnMut(&S) -> S) -> Cycle {
+    // Find the length of the cycle by moving the tortoise to the hare every power of two steps
+    let mut power = 1;
+    let mut len = 1;
+    let mut tortoise = init.clone();
+    let mut hare = step(&init);
+    while tortoise != hare {
+        if power == len {
+            tortoise = hare.clone();
+            power *= 2;
+            len = 0;
+        }
+        hare = step(&hare);
+        len += 1;
+    }
+
+    // Then find the start with a hare that is exactly one cycle ahead of the tortoise
+    let mut tortoise = init.clone();
+    let mut hare = init;
+    for _ in 0..len {
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    Cycle { start, len }
+}
+
+/// The state after `n` steps from `init`, skipping over repetitions of the cycle.
+pub fn nth_state<S: Clone + Eq>(init: S, mut step: impl FnMut(&S) -> S, n: usize) -> S {
+    let cycle = find_cycle(init.clone(), &mut step);
+
+    let mut state = init;
+    for _ in 0..cycle.equivalent_step(n) {
+        state = step(&state);
+    }
+
+    state
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random;
+
+    fn tilt_north(platform: &CharGrid) -> CharGrid {
+        let mut tilted = platform.clone();
+        for col in 0..tilted.ncols() {
+            let mut free_row = 0;
+            for row in 0..tilted.nrows() {
+                match tilted[(row, col)] {
+                    '#' => free_row = row + 1,
+                    'O' => {
+                        tilted[(row, col)] = '.';
+                        tilted[(free_row, col)] = 'O';
+                        free_row += 1;
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        tilted
+    }
+
+    fn spin_cycle(platform: &CharGrid) -> CharGrid {
+        (0..4).fold(platform.clone(), |p, _| tilt_north(&p).rotated())
+    }
+
+    fn load(platform: &CharGrid) -> i64 {
+        platform
+            .indexed_iter()
+            .filter(|&(_, c)| c == 'O')
+            .map(|((row, _), _)| platform.nrows() - row)
+            .sum()
+    }
+
+    #[test]
+    pub fn test_spin_cycle() {
+        let platform = CharGrid::from_text(
+            "\
+O....#....
+O.OO#....#
+.....##...
+OO.#O....O
+.O.....O#.
+O.#..O.#.#
+..O..#O..O
+.......O..
+#....###..
+#OO..#....
+",
+        )
+        .unwrap();
+
+        let states = detect_cycle(platform.clone(), spin_cycle);
+        assert_eq!(states.cycle, Cycle { start: 3, len: 7 });
+        assert_eq!(load(states.state_after(1_000_000_000)), 64);
+
+        let cycle = find_cycle(platform.clone(), spin_cycle);
+        assert_eq!(cycle, states.cycle);
+        assert_eq!(load(&nth_state(platform, spin_cycle, 1_000_000_000)), 64);
+    }
+
+    #[test]
+    pub fn test_hash_and_brent_agree_with_simulation() {
+        let mut rng = 0x1337;
+        for _ in 0..500 {
+            let n = 1 + random(&mut rng) % 20;
+            let next = (0..n).map(|_| random(&mut rng) % n).collect_vec();
+            let step = |&x: &u64| next[x as usize];
+            let init = random(&mut rng) % n;
+
+            let states = detect_cycle(init, step);
+            assert_eq!(find_cycle(init, step), states.cycle);
+
+            for steps in 0..50 {
+                let simulated = (0..steps).fold(init, |x, _| step(&x));
+                assert_eq!(*states.state_after(steps), simulated);
+                assert_eq!(nth_state(init, step, steps), simulated);
+            }
+        }
+    }
+
+    /// A state whose hashes all collide, to exercise comparing states with the same hash
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    struct Colliding(u64);
+
+    impl Hash for Colliding {
+        fn hash<H: std::hash::Hasher>(&self, _: &mut H) {}
+    }
+
+    #[test]
+    pub fn test_detect_cycle_with_hash_collisions() {
+        let states = detect_cycle(Colliding(3), |&Colliding(x)| Colliding((x * x + 1) % 17));
+
+        assert_eq!(states.cycle, Cycle { start: 1, len: 6 });
+        assert_eq!(*states.state_after(1_000_000_000), Colliding(5));
+    }
+
+    #[test]
+    pub fn test_extrapolate_with_fingerprint() {
+        // The position repeats every 4 steps, while the total keeps growing
+        let step = |&(pos, total): &(i64, i64)| ((pos + 3) % 4, total + pos);
+
+        let states = detect_cycle_by_key((1, 0), step, |&(pos, _)| pos);
+        assert_eq!(states.cycle, Cycle { start: 0, len: 4 });
+
+        for n in 0..40 {
+            let simulated = (0..n).fold((1, 0), |s, _| step(&s));
+            assert_eq!(states.extrapolate(n, |&(_, total)| total), simulated.1);
+        }
+        assert_eq!(
+            states.extrapolate(1_000_000_000_000, |&(_, total)| total),
+            1_000_000_000_000 / 4 * 6
+        );
+    }
+}
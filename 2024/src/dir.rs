@@ -0,0 +1,48 @@
+/// Directions on a grid
+use crate::*;
+
+/// One of the four directions on a grid.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Dir {
+    Up,
+    Right,
+    Down,
+    Left,
+}
+
+impl Dir {
+    pub const ALL: [Dir; 4] = [Dir::Up, Dir::Right, Dir::Down, Dir::Left];
+
+    /// Parses `U`/`R`/`D`/`L`, `N`/`E`/`S`/`W` or `^`/`>`/`v`/`<`.
+    pub fn from_char(c: char) -> Option<Dir> {
+        match c {
+            'U' | 'N' | '^' => Some(Dir::Up),
+            'R' | 'E' | '>' => Some(Dir::Right),
+            'D' | 'S' | 'v' => Some(Dir::Down),
+            'L' | 'W' | '<' => Some(Dir::Left),
+            _ => None,
+        }
+    }
+
+    /// The (row, col) step in this direction.
+    pub fn offset(&self) -> Idx {
+        match self {
+            Dir::Up => (-1, 0),
+            Dir::Right => (0, 1),
+            Dir::Down => (1, 0),
+            Dir::Left => (0, -1),
+        }
+    }
+
+    pub fn turn_right(&self) -> Dir {
+        Dir::ALL[(*self as usize + 1) % 4]
+    }
+
+    pub fn turn_left(&self) -> Dir {
+        Dir::ALL[(*self as usize + 3) % 4]
+    }
+
+    pub fn reverse(&self) -> Dir {
+        Dir::ALL[(*self as usize + 2) % 4]
+    }
+}
@@ -0,0 +1,606 @@
+/// Arithmetic expressions over named variables, evaluated exactly and solved for an unknown
+use crate::*;
+use std::cmp::Ordering;
+use std::fmt::Formatter;
+use std::iter;
+
+/// An exact fraction `num / den`, always stored in lowest terms with a positive denominator.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rational {
+    num: i128,
+    den: i128,
+}
+
+impl Rational {
+    pub const ZERO: Rational = Rational { num: 0, den: 1 };
+    pub const ONE: Rational = Rational { num: 1, den: 1 };
+
+    /// The fraction `num / den`, or `None` if `den` is zero.
+    pub fn new(num: i128, den: i128) -> Option<Rational> {
+        if den == 0 {
+            return None;
+        }
+
+        let g = gcd(num, den);
+        let sign = den.signum();
+        Some(Rational {
+            num: sign * num / g,
+            den: sign * den / g,
+        })
+    }
+
+    pub fn numer(&self) -> i128 {
+        self.num
+    }
+
+    pub fn denom(&self) -> i128 {
+        self.den
+    }
+
+    pub fn is_integer(&self) -> bool {
+        self.den == 1
+    }
+
+    pub fn to_integer(&self) -> Option<i128> {
+        self.is_integer().then_some(self.num)
+    }
+
+    pub fn checked_add(self, rhs: Rational) -> Option<Rational> {
+        let num = self
+            .num
+            .checked_mul(rhs.den)?
+            .checked_add(rhs.num.checked_mul(self.den)?)?;
+        Rational::new(num, self.den.checked_mul(rhs.den)?)
+    }
+
+    pub fn checked_sub(self, rhs: Rational) -> Option<Rational> {
+        self.checked_add(Rational {
+            num: rhs.num.checked_neg()?,
+            den: rhs.den,
+        })
+    }
+
+    pub fn checked_mul(self, rhs: Rational) -> Option<Rational> {
+        // Cross-cancel first to keep the intermediate products small
+        let (a, b) = (gcd(self.num, rhs.den), gcd(rhs.num, self.den));
+        Rational::new(
+            (self.num / a).checked_mul(rhs.num / b)?,
+            (self.den / b).checked_mul(rhs.den / a)?,
+        )
+    }
+
+    /// Divides by `rhs`, or returns `None` if `rhs` is zero or the result overflows.
+    pub fn checked_div(self, rhs: Rational) -> Option<Rational> {
+        self.checked_mul(Rational::new(rhs.den, rhs.num)?)
+    }
+}
+
+impl From<i64> for Rational {
+    fn from(value: i64) -> Self {
+        Rational {
+            num: value as i128,
+            den: 1,
+        }
+    }
+}
+
+impl PartialOrd for Rational {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Rational {
+    fn cmp(&self, other: &Self) -> Ordering {
+        let (mut a_num, mut a_den) = (self.num, self.den);
+        let (mut b_num, mut b_den) = (other.num, other.den);
+        loop {
+            if let (Some(a), Some(b)) = (a_num.checked_mul(b_den), b_num.checked_mul(a_den)) {
+                return a.cmp(&b);
+            }
+
+            // The cross products overflow, so compare the integer parts and then the fractional
+            // parts, the latter by comparing their reciprocals (as in a continued fraction)
+            let (a_int, a_rem) = (a_num.div_euclid(a_den), a_num.rem_euclid(a_den));
+            let (b_int, b_rem) = (b_num.div_euclid(b_den), b_num.rem_euclid(b_den));
+            if a_int != b_int {
+                return a_int.cmp(&b_int);
+            }
+
+            match (a_rem, b_rem) {
+                (0, 0) => return Ordering::Equal,
+                (0, _) => return Ordering::Less,
+                (_, 0) => return Ordering::Greater,
+                _ => (a_num, a_den, b_num, b_den) = (b_den, b_rem, a_den, a_rem),
+            }
+        }
+    }
+}
+
+impl Display for Rational {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        if self.is_integer() {
+            write!(f, "{}", self.num)
+        } else {
+            write!(f, "{}/{}", self.num, self.den)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Op {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl Op {
+    pub fn from_symbol(symbol: &str) -> Option<Op> {
+        match symbol {
+            "+" => Some(Op::Add),
+            "-" => Some(Op::Sub),
+            "*" => Some(Op::Mul),
+            "/" => Some(Op::Div),
+            _ => None,
+        }
+    }
+
+    pub fn apply(&self, left: Rational, right: Rational) -> Result<Rational> {
+        let result = match self {
+            Op::Add => left.checked_add(right),
+            Op::Sub => left.checked_sub(right),
+            Op::Mul => left.checked_mul(right),
+            Op::Div if right == Rational::ZERO => {
+                return Err(eyre!("division by zero: {} / {}", left, right))
+            }
+            Op::Div => left.checked_div(right),
+        };
+
+        result.ok_or_else(|| eyre!("overflow while computing {} {:?} {}", left, self, right))
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Expr {
+    Num(Rational),
+    Var(String),
+    BinOp(Box<Expr>, Op, Box<Expr>),
+}
+
+impl Expr {
+    pub fn num(value: i64) -> Expr {
+        Expr::Num(value.into())
+    }
+
+    pub fn var(name: &str) -> Expr {
+        Expr::Var(name.to_string())
+    }
+
+    pub fn bin_op(left: Expr, op: Op, right: Expr) -> Expr {
+        Expr::BinOp(Box::new(left), op, Box::new(right))
+    }
+
+    /// Parses a number, a variable name, or two of those separated by an operator, such as
+    /// `"pppw + sjmn"`.
+    pub fn parse(text: &str) -> Result<Expr> {
+        let operand = |token: &str| -> Result<Expr> {
+            if let Ok(value) = token.parse::<i64>() {
+                Ok(Expr::num(value))
+            } else if !token.is_empty() && token.chars().all(|c| c.is_alphanumeric() || c == '_') {
+                Ok(Expr::var(token))
+            } else {
+                Err(eyre!("invalid operand {:?}", token))
+            }
+        };
+
+        let tokens = text.split_whitespace().collect_vec();
+        match tokens[..] {
+            [token] => operand(token),
+            [left, op, right] => {
+                let op = Op::from_symbol(op).ok_or_else(|| eyre!("invalid operator {:?}", op))?;
+                Ok(Expr::bin_op(operand(left)?, op, operand(right)?))
+            }
+            _ => Err(eyre!("invalid expression {:?}", text)),
+        }
+    }
+}
+
+/// A set of named expressions that may refer to each other, forming a DAG.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let exprs = ExprGraph::parse("
+///     root: a + b
+///     a: x * 2
+///     b: 1 / 3
+///     x: 4
+/// ").unwrap();
+///
+/// assert_eq!(exprs.eval("root").unwrap().to_string(), "25/3");
+/// assert_eq!(exprs.solve("a", "b", "x").unwrap().to_string(), "1/6");
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ExprGraph {
+    definitions: HashMap<String, Expr>,
+}
+
+impl ExprGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Parses lines of the form `name: expression`, skipping empty lines.
+    pub fn parse(input: &str) -> Result<ExprGraph> {
+        let mut graph = ExprGraph::new();
+        for line in input.lines().map(str::trim).filter(|line| !line.is_empty()) {
+            let (name, expr) = line
+                .split_once(':')
+                .ok_or_else(|| eyre!("expected \"name: expression\", found {:?}", line))?;
+            let expr = Expr::parse(expr).wrap_err_with(|| format!("while parsing {:?}", line))?;
+
+            graph.define(name.trim(), expr);
+        }
+
+        Ok(graph)
+    }
+
+    pub fn define(&mut self, name: &str, expr: Expr) {
+        self.definitions.insert(name.to_string(), expr);
+    }
+
+    pub fn get(&self, name: &str) -> Option<&Expr> {
+        self.definitions.get(name)
+    }
+
+    pub fn eval(&self, name: &str) -> Result<Rational> {
+        Evaluator::new(self, None).eval_var(name)
+    }
+
+    /// Finds the value of `unknown` (ignoring its definition, if any) for which `lhs` and `rhs`
+    /// evaluate to the same value.
+    ///
+    /// If `unknown` is used only once, the equation is solved exactly by undoing the operations
+    /// around it one at a time. Otherwise, an integer solution is searched for numerically.
+    pub fn solve(&self, lhs: &str, rhs: &str, unknown: &str) -> Result<Rational> {
+        let mut counter = Evaluator::new(self, Some(unknown));
+        let uses = counter.count_uses(lhs, unknown)? + counter.count_uses(rhs, unknown)?;
+        match uses {
+            0 => Err(eyre!("{:?} does not depend on {:?}", [lhs, rhs], unknown)),
+            1 => self.solve_by_inversion(lhs, rhs, unknown),
+            _ => self.solve_numerically(lhs, rhs, unknown),
+        }
+    }
+
+    fn solve_by_inversion(&self, lhs: &str, rhs: &str, unknown: &str) -> Result<Rational> {
+        let mut evaluator = Evaluator::new(self, Some(unknown));
+        let (mut expr, mut target) = if evaluator.count_uses(lhs, unknown)? > 0 {
+            (Expr::var(lhs), evaluator.eval_var(rhs)?)
+        } else {
+            (Expr::var(rhs), evaluator.eval_var(lhs)?)
+        };
+
+        loop {
+            match expr {
+                Expr::Var(name) if name == unknown => return Ok(target),
+                Expr::Var(name) => expr = self.definition(&name)?.clone(),
+                Expr::Num(_) => return Err(eyre!("{:?} not found while solving", unknown)),
+                Expr::BinOp(left, op, right) => {
+                    if evaluator.count_uses_in(&left, unknown)? > 0 {
+                        let known = evaluator.eval_expr(&right)?;
+                        target = match op {
+                            Op::Add => Op::Sub.apply(target, known)?,
+                            Op::Sub => Op::Add.apply(target, known)?,
+                            Op::Mul => Op::Div.apply(target, known)?,
+                            Op::Div => Op::Mul.apply(target, known)?,
+                        };
+                        expr = *left;
+                    } else {
+                        let known = evaluator.eval_expr(&left)?;
+                        target = match op {
+                            Op::Add => Op::Sub.apply(target, known)?,
+                            Op::Sub => Op::Sub.apply(known, target)?,
+                            Op::Mul => Op::Div.apply(target, known)?,
+                            Op::Div => Op::Div.apply(known, target)?,
+                        };
+                        expr = *right;
+                    }
+                }
+            }
+        }
+    }
+
+    fn solve_numerically(&self, lhs: &str, rhs: &str, unknown: &str) -> Result<Rational> {
+        // Values for which either side fails to evaluate, such as a division by zero or an
+        // overflow, give no information about the sign and are skipped
+        let difference = |x: i128| -> Option<Ordering> {
+            let value = Rational { num: x, den: 1 };
+            let mut evaluator = Evaluator::new(self, None).with_value(unknown, value);
+
+            let lhs = evaluator.eval_var(lhs).ok()?;
+            let rhs = evaluator.eval_var(rhs).ok()?;
+            Some(lhs.cmp(&rhs))
+        };
+
+        // Search outwards from 0 in both directions for a sign change, then bisect
+        for direction in [1, -1] {
+            let mut prev: Option<(i128, Ordering)> = None;
+            for x in iter::once(0).chain((0..100).map(|k| direction * (1i128 << k))) {
+                let Some(sign) = difference(x) else {
+                    continue;
+                };
+                if sign == Ordering::Equal {
+                    return Ok(Rational { num: x, den: 1 });
+                }
+
+                match prev {
+                    Some((same, prev_sign)) if prev_sign != sign => {
+                        return Self::bisect(same, x, prev_sign, difference).ok_or_else(|| {
+                            eyre!(
+                                "no integer value of {:?} between {} and {} solves the equation",
+                                unknown,
+                                same,
+                                x
+                            )
+                        });
+                    }
+                    _ => prev = Some((x, sign)),
+                }
+            }
+        }
+
+        Err(eyre!("no solution found for {:?}", unknown))
+    }
+
+    /// Narrows down `same..other` to the integer where `difference` becomes `Equal`, given that
+    /// it is `same_sign` at `same` and the opposite at `other`.
+    fn bisect(
+        mut same: i128,
+        mut other: i128,
+        same_sign: Ordering,
+        difference: impl Fn(i128) -> Option<Ordering>,
+    ) -> Option<Rational> {
+        while (other - same).abs() > 1 {
+            let mid = same + (other - same) / 2;
+
+            // If the midpoint cannot be evaluated, probe the closest point strictly between
+            // `same` and `other` that can, giving up if none of the nearest ones can
+            let step = (other - same).signum();
+            let (probe, sign) = (0..(other - same).abs())
+                .flat_map(|d| [mid + d * step, mid - d * step])
+                .filter(|&x| (x - same) * step > 0 && (other - x) * step > 0)
+                .take(64)
+                .find_map(|x| Some((x, difference(x)?)))?;
+            match sign {
+                Ordering::Equal => return Some(Rational { num: probe, den: 1 }),
+                sign if sign == same_sign => same = probe,
+                _ => other = probe,
+            }
+        }
+
+        None
+    }
+
+    fn definition(&self, name: &str) -> Result<&Expr> {
+        self.get(name)
+            .ok_or_else(|| eyre!("undefined variable {:?}", name))
+    }
+}
+
+/// Evaluates variables with memoization, detecting cyclic references.
+struct Evaluator<'a> {
+    graph: &'a ExprGraph,
+    /// A variable that has no value, even if it is defined
+    unknown: Option<&'a str>,
+    values: HashMap<String, Rational>,
+    uses: HashMap<String, u64>,
+    /// The variables currently being evaluated, to detect cycles
+    stack: Vec<String>,
+}
+
+impl<'a> Evaluator<'a> {
+    fn new(graph: &'a ExprGraph, unknown: Option<&'a str>) -> Self {
+        Evaluator {
+            graph,
+            unknown,
+            values: HashMap::default(),
+            uses: HashMap::default(),
+            stack: vec![],
+        }
+    }
+
+    /// Uses `value` for the variable `name`, instead of its definition.
+    fn with_value(mut self, name: &str, value: Rational) -> Self {
+        self.values.insert(name.to_string(), value);
+        self
+    }
+
+    fn enter(&mut self, name: &str) -> Result<&'a Expr> {
+        if let Some(pos) = self.stack.iter().position(|n| n == name) {
+            let mut cycle = self.stack[pos..].to_vec();
+            cycle.push(name.to_string());
+            return Err(eyre!("cyclic reference: {}", cycle.join(" -> ")));
+        }
+
+        self.stack.push(name.to_string());
+        self.graph.definition(name)
+    }
+
+    fn eval_var(&mut self, name: &str) -> Result<Rational> {
+        if let Some(&value) = self.values.get(name) {
+            return Ok(value);
+        }
+        if self.unknown == Some(name) {
+            return Err(eyre!("{:?} is unknown", name));
+        }
+
+        let expr = self.enter(name)?;
+        let value = self
+            .eval_expr(expr)
+            .wrap_err_with(|| format!("while evaluating {:?}", name))?;
+        self.stack.pop();
+
+        self.values.insert(name.to_string(), value);
+        Ok(value)
+    }
+
+    fn eval_expr(&mut self, expr: &Expr) -> Result<Rational> {
+        match expr {
+            Expr::Num(value) => Ok(*value),
+            Expr::Var(name) => self.eval_var(name),
+            Expr::BinOp(left, op, right) => {
+                let left = self.eval_expr(left)?;
+                let right = self.eval_expr(right)?;
+                op.apply(left, right)
+            }
+        }
+    }
+
+    /// The number of times `unknown` is used when expanding `name`, counting shared
+    /// subexpressions once for every use.
+    fn count_uses(&mut self, name: &str, unknown: &str) -> Result<u64> {
+        if name == unknown {
+            return Ok(1);
+        }
+        if let Some(&uses) = self.uses.get(name) {
+            return Ok(uses);
+        }
+
+        let expr = self.enter(name)?;
+        let uses = self.count_uses_in(expr, unknown)?;
+        self.stack.pop();
+
+        self.uses.insert(name.to_string(), uses);
+        Ok(uses)
+    }
+
+    fn count_uses_in(&mut self, expr: &Expr, unknown: &str) -> Result<u64> {
+        match expr {
+            Expr::Num(_) => Ok(0),
+            Expr::Var(name) => self.count_uses(name, unknown),
+            Expr::BinOp(left, _, right) => Ok(self
+                .count_uses_in(left, unknown)?
+                .saturating_add(self.count_uses_in(right, unknown)?)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MONKEYS: &str = "
+        root: pppw + sjmn
+        dbpl: 5
+        cczh: sllz + lgvd
+        zczc: 2
+        ptdq: humn - dvpt
+        dvpt: 3
+        lfqf: 4
+        humn: 5
+        ljgn: 2
+        sjmn: drzm * dbpl
+        sllz: 4
+        pppw: cczh / lfqf
+        lgvd: ljgn * ptdq
+        drzm: hmdt - zczc
+        hmdt: 32
+    ";
+
+    #[test]
+    pub fn test_monkey_math() {
+        let monkeys = ExprGraph::parse(MONKEYS).unwrap();
+
+        assert_eq!(monkeys.eval("root").unwrap(), Rational::from(152));
+        assert_eq!(
+            monkeys.solve("pppw", "sjmn", "humn").unwrap(),
+            Rational::from(301)
+        );
+        assert_eq!(
+            monkeys.solve_numerically("pppw", "sjmn", "humn").unwrap(),
+            Rational::from(301)
+        );
+    }
+
+    #[test]
+    pub fn test_solve_with_repeated_unknown() {
+        let mut exprs = ExprGraph::parse("lhs: x * x\nrhs: 49").unwrap();
+        assert_eq!(exprs.solve("lhs", "rhs", "x").unwrap(), Rational::from(7));
+
+        exprs.define("lhs", Expr::bin_op(Expr::var("x"), Op::Mul, Expr::num(-2)));
+        exprs.define(
+            "rhs",
+            Expr::bin_op(Expr::var("x"), Op::Add, Expr::num(-300)),
+        );
+        assert_eq!(exprs.solve("lhs", "rhs", "x").unwrap(), Rational::from(100));
+
+        exprs.define("lhs", Expr::bin_op(Expr::var("x"), Op::Mul, Expr::var("x")));
+        exprs.define("rhs", Expr::num(50));
+        assert!(exprs.solve("lhs", "rhs", "x").is_err());
+    }
+
+    #[test]
+    pub fn test_solve_skips_values_that_fail_to_evaluate() {
+        // 100 / x - x = 15 cannot be evaluated at x = 0
+        let exprs = ExprGraph::parse("lhs: a - x\na: d / x\nd: 100\nrhs: 15").unwrap();
+        assert_eq!(exprs.solve("lhs", "rhs", "x").unwrap(), Rational::from(5));
+
+        // x * x * x = -8 overflows for large positive x before the negative side is searched
+        let mut exprs = ExprGraph::parse("lhs: sq * x\nsq: x * x").unwrap();
+        exprs.define("rhs", Expr::num(-8));
+        assert_eq!(exprs.solve("lhs", "rhs", "x").unwrap(), Rational::from(-2));
+    }
+
+    #[test]
+    pub fn test_cyclic_reference() {
+        let exprs = ExprGraph::parse("a: b + 1\nb: c * 2\nc: a - 3\nd: 4").unwrap();
+
+        let err = exprs.eval("a").unwrap_err();
+        assert!(format!("{:?}", err).contains("cyclic reference: a -> b -> c -> a"));
+        assert!(exprs.solve("a", "d", "x").is_err());
+        assert_eq!(exprs.eval("d").unwrap(), Rational::from(4));
+    }
+
+    #[test]
+    pub fn test_rational() {
+        let third = Rational::new(2, -6).unwrap();
+        assert_eq!((third.numer(), third.denom()), (-1, 3));
+        assert_eq!(
+            third.checked_mul(Rational::from(-3)).unwrap(),
+            Rational::ONE
+        );
+        assert!(Rational::new(1, 0).is_none());
+        assert!(Op::Div.apply(Rational::ONE, Rational::ZERO).is_err());
+        assert!(Rational::from(i64::MAX)
+            .checked_mul(Rational::from(i64::MAX))
+            .unwrap()
+            .checked_mul(Rational::from(4))
+            .is_none());
+    }
+
+    #[test]
+    pub fn test_compare_large_rationals() {
+        let max = i128::MAX;
+        let ordered = [
+            Rational::new(-max, 2).unwrap(),
+            Rational::new(-max + 2, 2).unwrap(),
+            Rational::new(-1, max).unwrap(),
+            Rational::ZERO,
+            Rational::new(1, max).unwrap(),
+            Rational::new(1, max - 1).unwrap(),
+            Rational::new(max - 1, max).unwrap(),
+            Rational::ONE,
+            Rational::new(max, max - 1).unwrap(),
+            Rational::new(max - 2, 2).unwrap(),
+            Rational::new(max, 2).unwrap(),
+        ];
+
+        for (i, a) in ordered.iter().enumerate() {
+            for (j, b) in ordered.iter().enumerate() {
+                assert_eq!(a.cmp(b), i.cmp(&j), "{} vs {}", a, b);
+            }
+        }
+    }
+}
@@ -1,21 +1,16 @@
-use crate::HashMap;
+use crate::Counter;
 use eyre::eyre;
 use num_traits::PrimInt;
 /// Extensions to some standard library types, such as Iterator and Option.
 use std::hash::Hash;
 
 pub trait Frequencies<FreqType: PrimInt>: Iterator {
-    fn frequencies(self) -> HashMap<Self::Item, FreqType>
+    fn frequencies(self) -> Counter<Self::Item, FreqType>
     where
         Self: Sized,
         Self::Item: Eq + Hash,
     {
-        let mut counts = HashMap::default();
-        self.for_each(|item| {
-            let entry = counts.entry(item).or_insert_with(FreqType::zero);
-            *entry = entry.add(FreqType::one());
-        });
-        counts
+        self.map(|item| (item, FreqType::one())).collect()
     }
 }
 
@@ -70,7 +65,11 @@ pub trait OptionTools<T> {
 }
 
 impl<T> OptionTools<T> for Option<T> {
+    /// Turns `None` into an error naming the source location of the call, since there is no
+    /// input to point at.
+    #[track_caller]
     fn ok_or_parse_error(self) -> crate::Result<T> {
-        self.ok_or(eyre!("parse error"))
+        let caller = std::panic::Location::caller();
+        self.ok_or_else(|| eyre!("parse error at {}", caller))
     }
 }
@@ -0,0 +1,374 @@
+/// Integer intervals and sets of intervals
+use num_traits::PrimInt;
+
+/// A half-open interval `[start, end)` of integers. Intervals with `end <= start` are empty.
+///
+/// Example:
+/// ```
+/// use aoc2024::Interval;
+///
+/// let a = Interval::inclusive(2, 6);
+/// let b = Interval::new(5, 10);
+///
+/// assert_eq!(a.len(), 5);
+/// assert_eq!(a.intersection(&b), Interval::new(5, 7));
+/// assert_eq!(a.difference(&b), (Interval::new(2, 5), Interval::empty()));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct Interval<T> {
+    pub start: T,
+    pub end: T,
+}
+
+impl<T: PrimInt> Interval<T> {
+    pub fn new(start: T, end: T) -> Self {
+        Interval { start, end }
+    }
+
+    /// The interval from `first` up to and including `last`.
+    pub fn inclusive(first: T, last: T) -> Self {
+        Interval {
+            start: first,
+            end: last + T::one(),
+        }
+    }
+
+    pub fn empty() -> Self {
+        Interval {
+            start: T::zero(),
+            end: T::zero(),
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.end <= self.start
+    }
+
+    /// The number of integers in the interval.
+    pub fn len(&self) -> T {
+        if self.is_empty() {
+            T::zero()
+        } else {
+            self.end - self.start
+        }
+    }
+
+    /// The last integer in the interval, if any.
+    pub fn last(&self) -> Option<T> {
+        (!self.is_empty()).then(|| self.end - T::one())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        self.start <= value && value < self.end
+    }
+
+    pub fn contains_interval(&self, other: &Self) -> bool {
+        other.is_empty() || (self.start <= other.start && other.end <= self.end)
+    }
+
+    pub fn overlaps(&self, other: &Self) -> bool {
+        !self.intersection(other).is_empty()
+    }
+
+    /// The intersection of both intervals, which is empty if they do not overlap.
+    pub fn intersection(&self, other: &Self) -> Self {
+        let intersection = Interval::new(self.start.max(other.start), self.end.min(other.end));
+        if intersection.is_empty() {
+            Self::empty()
+        } else {
+            intersection
+        }
+    }
+
+    /// The parts of this interval before and after `other`, either of which may be empty.
+    pub fn difference(&self, other: &Self) -> (Self, Self) {
+        if self.is_empty() {
+            return (Self::empty(), Self::empty());
+        }
+
+        if !self.overlaps(other) {
+            return (*self, Self::empty());
+        }
+
+        let before = Interval::new(self.start, other.start);
+        let after = Interval::new(other.end, self.end);
+        let non_empty = |i: Self| if i.is_empty() { Self::empty() } else { i };
+
+        (non_empty(before), non_empty(after))
+    }
+
+    /// Shifts the interval by `offset`.
+    pub fn shifted(&self, offset: T) -> Self {
+        Interval::new(self.start + offset, self.end + offset)
+    }
+}
+
+/// A set of integers, stored as sorted, disjoint and non-adjacent intervals.
+///
+/// Example:
+/// ```
+/// use aoc2024::{Interval, IntervalSet};
+///
+/// let mut covered = IntervalSet::new();
+/// covered.insert(Interval::inclusive(-2, 2));
+/// covered.insert(Interval::inclusive(12, 12));
+/// covered.insert(Interval::inclusive(2, 14));
+/// covered.remove(Interval::inclusive(5, 6));
+///
+/// assert_eq!(covered.len(), 15);
+/// assert_eq!(covered.intervals(), [Interval::new(-2, 5), Interval::new(7, 15)]);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct IntervalSet<T> {
+    intervals: Vec<Interval<T>>,
+}
+
+impl<T: PrimInt> IntervalSet<T> {
+    pub fn new() -> Self {
+        IntervalSet { intervals: vec![] }
+    }
+
+    pub fn intervals(&self) -> &[Interval<T>] {
+        &self.intervals
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.intervals.is_empty()
+    }
+
+    /// The number of integers in the set.
+    pub fn len(&self) -> T {
+        self.intervals
+            .iter()
+            .fold(T::zero(), |len, interval| len + interval.len())
+    }
+
+    pub fn min(&self) -> Option<T> {
+        self.intervals.first().map(|i| i.start)
+    }
+
+    pub fn max(&self) -> Option<T> {
+        self.intervals.last().and_then(|i| i.last())
+    }
+
+    pub fn contains(&self, value: T) -> bool {
+        // Index of the first interval that ends after value
+        let idx = self.intervals.partition_point(|i| i.end <= value);
+        self.intervals.get(idx).is_some_and(|i| i.contains(value))
+    }
+
+    pub fn contains_interval(&self, interval: &Interval<T>) -> bool {
+        if interval.is_empty() {
+            return true;
+        }
+
+        let idx = self.intervals.partition_point(|i| i.end <= interval.start);
+        self.intervals
+            .get(idx)
+            .is_some_and(|i| i.contains_interval(interval))
+    }
+
+    pub fn insert(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        // Intervals that overlap or touch the new one are merged into it
+        let first = self.intervals.partition_point(|i| i.end < interval.start);
+        let last = self.intervals.partition_point(|i| i.start <= interval.end);
+
+        let mut merged = interval;
+        if first < last {
+            merged.start = merged.start.min(self.intervals[first].start);
+            merged.end = merged.end.max(self.intervals[last - 1].end);
+        }
+
+        self.intervals.splice(first..last, [merged]);
+    }
+
+    pub fn remove(&mut self, interval: Interval<T>) {
+        if interval.is_empty() {
+            return;
+        }
+
+        let first = self.intervals.partition_point(|i| i.end <= interval.start);
+        let last = self.intervals.partition_point(|i| i.start < interval.end);
+        if first >= last {
+            return;
+        }
+
+        let (before, _) = self.intervals[first].difference(&interval);
+        let (_, after) = self.intervals[last - 1].difference(&interval);
+        let remaining = [before, after].into_iter().filter(|i| !i.is_empty());
+
+        self.intervals.splice(first..last, remaining);
+    }
+
+    pub fn union(&self, other: &Self) -> Self {
+        let mut union = self.clone();
+        for &interval in &other.intervals {
+            union.insert(interval);
+        }
+
+        union
+    }
+
+    pub fn intersection(&self, other: &Self) -> Self {
+        let mut intersection = vec![];
+        let (mut i, mut j) = (0, 0);
+        while i < self.intervals.len() && j < other.intervals.len() {
+            let (a, b) = (self.intervals[i], other.intervals[j]);
+            let overlap = a.intersection(&b);
+            if !overlap.is_empty() {
+                intersection.push(overlap);
+            }
+
+            if a.end < b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+
+        IntervalSet {
+            intervals: intersection,
+        }
+    }
+
+    pub fn difference(&self, other: &Self) -> Self {
+        let mut difference = self.clone();
+        for &interval in &other.intervals {
+            difference.remove(interval);
+        }
+
+        difference
+    }
+
+    /// Iterates over all integers in the set.
+    pub fn iter(&self) -> impl Iterator<Item = T> + '_ {
+        self.intervals
+            .iter()
+            .flat_map(|i| int_range(i.start, i.end))
+    }
+}
+
+impl<T: PrimInt> FromIterator<Interval<T>> for IntervalSet<T> {
+    fn from_iter<I: IntoIterator<Item = Interval<T>>>(iter: I) -> Self {
+        let mut set = IntervalSet::new();
+        for interval in iter {
+            set.insert(interval);
+        }
+
+        set
+    }
+}
+
+impl<T: PrimInt> From<Interval<T>> for IntervalSet<T> {
+    fn from(interval: Interval<T>) -> Self {
+        IntervalSet::from_iter([interval])
+    }
+}
+
+fn int_range<T: PrimInt>(start: T, end: T) -> impl Iterator<Item = T> {
+    let mut current = start;
+    std::iter::from_fn(move || {
+        if current < end {
+            let value = current;
+            current = current + T::one();
+            Some(value)
+        } else {
+            None
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random;
+    use crate::Itertools;
+
+    /// Sets of integers in 0..32 as bitmasks, to check against
+    type Model = u32;
+
+    fn to_model(set: &IntervalSet<i32>) -> Model {
+        set.iter().fold(0, |model, value| model | (1 << value))
+    }
+
+    fn random_interval(state: &mut u64) -> Interval<i32> {
+        let start = (random(state) % 32) as i32;
+        let len = (random(state) % 8) as i32;
+        Interval::new(start, (start + len).min(32))
+    }
+
+    fn random_set(state: &mut u64) -> IntervalSet<i32> {
+        let n = random(state) % 5;
+        (0..n).map(|_| random_interval(state)).collect()
+    }
+
+    fn is_normalized(set: &IntervalSet<i32>) -> bool {
+        set.intervals().iter().all(|i| !i.is_empty())
+            && set
+                .intervals()
+                .iter()
+                .tuple_windows()
+                .all(|(a, b)| a.end < b.start)
+    }
+
+    #[test]
+    pub fn test_set_operations_match_model() {
+        let mut state = 0x2024;
+        for _ in 0..5000 {
+            let (a, b) = (random_set(&mut state), random_set(&mut state));
+            let (ma, mb) = (to_model(&a), to_model(&b));
+
+            for (result, expected) in [
+                (a.union(&b), ma | mb),
+                (a.intersection(&b), ma & mb),
+                (a.difference(&b), ma & !mb),
+            ] {
+                assert!(is_normalized(&result), "{:?}", result);
+                assert_eq!(to_model(&result), expected);
+                assert_eq!(result.len() as u32, expected.count_ones());
+            }
+
+            for value in 0..32 {
+                assert_eq!(a.contains(value), ma & (1 << value) != 0);
+            }
+
+            let interval = random_interval(&mut state);
+            let interval_model = to_model(&interval.into());
+            assert_eq!(
+                a.contains_interval(&interval),
+                ma & interval_model == interval_model
+            );
+        }
+    }
+
+    #[test]
+    pub fn test_interval_operations_match_model() {
+        let mut state = 0xdec;
+        for _ in 0..5000 {
+            let (a, b) = (random_interval(&mut state), random_interval(&mut state));
+            let (ma, mb) = (to_model(&a.into()), to_model(&b.into()));
+
+            assert_eq!(to_model(&a.intersection(&b).into()), ma & mb);
+            assert_eq!(a.overlaps(&b), ma & mb != 0);
+            assert_eq!(a.contains_interval(&b), ma & mb == mb);
+
+            let (before, after) = a.difference(&b);
+            assert_eq!(to_model(&IntervalSet::from_iter([before, after])), ma & !mb);
+            assert!(before.is_empty() || after.is_empty() || before.end < after.start);
+        }
+    }
+
+    #[test]
+    pub fn test_inclusive() {
+        let interval = Interval::inclusive(3u64, 5);
+
+        assert_eq!(interval, Interval::new(3, 6));
+        assert_eq!(interval.last(), Some(5));
+        assert_eq!(interval.len(), 3);
+        assert!(Interval::<u64>::empty().last().is_none());
+    }
+}
@@ -1,9 +1,31 @@
+mod animation;
 mod array;
+mod array_nd;
+mod bits;
+mod box_nd;
+mod branch_and_bound;
+mod byte_grid;
+mod cell;
 mod char_grid;
+mod counter;
+mod cycle;
+mod dir;
+mod expr;
 mod ext;
+mod interval;
 mod mat;
 mod mat_ops;
+mod memo;
+mod num;
+mod parse_error;
 mod parsing;
+mod polygon;
+mod range_map;
+mod render;
+#[cfg(test)]
+mod test_util;
+mod vec3;
+mod vm;
 
 use std::fmt::{Debug, Display};
 use std::io;
@@ -16,11 +38,31 @@ pub use std::collections::VecDeque;
 pub type HashSet<V> = FxHashSet<V>;
 pub type HashMap<K, V> = FxHashMap<K, V>;
 
+pub use animation::*;
 pub use array::*;
+pub use array_nd::*;
+pub use bits::*;
+pub use box_nd::*;
+pub use branch_and_bound::*;
+pub use byte_grid::*;
+pub use cell::*;
 pub use char_grid::*;
+pub use counter::*;
+pub use cycle::*;
+pub use dir::*;
+pub use expr::*;
 pub use ext::*;
+pub use interval::*;
 pub use mat::*;
+pub use memo::*;
+pub use num::*;
+pub use parse_error::*;
 pub use parsing::*;
+pub use polygon::*;
+pub use range_map::*;
+pub use render::*;
+pub use vec3::*;
+pub use vm::*;
 
 pub use eyre::eyre;
 pub use eyre::Context;
@@ -54,7 +96,7 @@ where
     Task2Fn: Fn(&str) -> Result<Task2Output>,
 {
     let task1_test_output = (solution.task_1)(solution.test_input)
-        .wrap_err_with(|| "while running task 1 on test input")?;
+        .wrap_err_with(|| "while running task 1 on the test input")?;
 
     if task1_test_output == solution.expected_1 {
         print!("[TEST OK] ");
@@ -68,12 +110,12 @@ where
 
     let task1_start = Instant::now();
     let task1_output =
-        (solution.task_1)(input).wrap_err_with(|| "while running task 1 on input")?;
+        (solution.task_1)(input).wrap_err_with(|| "while running task 1 on the real input")?;
     let task1_time = task1_start.elapsed();
     println!("Task 1: {}", task1_output);
 
     let task2_test_output = (solution.task_2)(solution.test_input_2)
-        .wrap_err_with(|| "while running task 2 on test input")?;
+        .wrap_err_with(|| "while running task 2 on the test input")?;
 
     if task2_test_output == solution.expected_2 {
         print!("[TEST OK] ");
@@ -87,7 +129,7 @@ where
 
     let task2_start = Instant::now();
     let task2_output =
-        (solution.task_2)(input).wrap_err_with(|| "while running task 2 on input")?;
+        (solution.task_2)(input).wrap_err_with(|| "while running task 2 on the real input")?;
     let task2_time = task2_start.elapsed();
     println!("Task 2: {}", task2_output);
 
@@ -118,7 +160,14 @@ macro_rules! aoc_main {
             let start = Instant::now();
             color_eyre::install().unwrap();
 
-            let timing_info = run(&input, &solution).unwrap();
+            let timing_info = match run(&input, &solution) {
+                Ok(timing_info) => timing_info,
+                Err(err) => {
+                    // Parse errors include a snippet of the input, which reads better than a panic
+                    eprintln!("\n{:?}", err);
+                    std::process::exit(1);
+                }
+            };
             let total = timing_info.task_1 + timing_info.task_2;
             println!("task 1: {:?} | task 2: {:?} | total: {:?}", timing_info.task_1, timing_info.task_2, total)
         }
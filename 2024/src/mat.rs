@@ -7,40 +7,48 @@ pub type Mat = Array2D<i64>;
 
 impl Mat {
     pub fn from_digits(raw_input: &str) -> Result<Mat> {
-        let cols = raw_input
-            .lines()
-            .next()
-            .map(|l| l.len())
-            .ok_or_else(|| eyre!("empty input"))?;
+        let lines = grid_lines(raw_input)?;
+        let rows = lines.len();
+        let cols = lines[0].1.chars().count();
 
-        let rows = raw_input.lines().count();
-
-        let data: Vec<i64> = raw_input
-            .replace("\n", "")
-            .chars()
-            .map(|c| c.to_digit(10).map(|d| d as i64).ok_or_parse_error())
-            .collect::<Result<_>>()?;
+        let mut data = Vec::with_capacity(rows * cols);
+        for &(line_offset, line) in &lines {
+            for (byte_idx, c) in line.char_indices() {
+                let digit = c.to_digit(10).ok_or_else(|| {
+                    let offset = line_offset + byte_idx;
+                    ParseError::at_offset(raw_input, offset, "a digit", format!("{:?}", c))
+                })?;
+                data.push(digit as i64);
+            }
+        }
 
-        Ok(Self::from_shape_vec((rows as i64, cols as i64), data))
+        Self::try_from_shape_vec((rows as i64, cols as i64), data)
     }
 
     pub fn from_text(input: &str) -> Result<Mat> {
-        let mut lines = input.lines();
-        let first_line = lines.next().ok_or(eyre!("empty input"))?;
-
-        let mut data = parse_nums(first_line)?;
-        let ncols = data.len() as i64;
-        let mut nrows = 1;
-        for line in lines {
-            nrows += 1;
-            let row = parse_nums(line)?;
-            if row.len() != ncols as usize {
-                return Err(eyre!("unexpected row length {:?}, expected {:?} based on the length of the first row", row.len(), ncols));
+        let lines = text_lines(input);
+        let Some(&(_, first_line)) = lines.first() else {
+            return Err(eyre!("empty input"));
+        };
+
+        let ncols = parse_nums::<i64>(first_line)?.len();
+        let mut data = Vec::with_capacity(lines.len() * ncols);
+        for (line_idx, &(line_offset, line)) in lines.iter().enumerate() {
+            let row: Vec<i64> =
+                parse_nums(line).wrap_err_with(|| format!("on line {}", line_idx + 1))?;
+            if row.len() != ncols {
+                return Err(ParseError::at_offset(
+                    input,
+                    line_offset + line.len(),
+                    format!("{} numbers, as on the first row", ncols),
+                    format!("{} numbers", row.len()),
+                )
+                .into());
             }
-            data.extend(parse_nums(line)?.iter())
+            data.extend(row)
         }
 
-        Ok(Self::from_shape_vec((nrows, ncols), data))
+        Self::try_from_shape_vec((lines.len() as i64, ncols as i64), data)
     }
 
     pub fn zeros(shape: (i64, i64)) -> Mat {
@@ -82,7 +90,11 @@ mod tests {
             7 8 9 10 11
         ".trim(); // second row has one less element
         
-        assert!(Mat::from_text(text).is_err())
+        let err = Mat::from_text(text).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.line_no, Some(2));
+        assert_eq!(err.column, 24);
+        assert_eq!(err.found, "5 numbers");
     }
     
     #[test]
@@ -101,6 +113,16 @@ mod tests {
         )
     }
     
+    #[test]
+    pub fn from_digits_should_report_byte_offset_of_invalid_digit() {
+        let input = "123\r\n4x6\r\n789\r\n";
+
+        assert_eq!(
+            Mat::from_digits(input).unwrap_err().to_string(),
+            "expected a digit, found 'x' at line 2, column 2 (byte offset 6)\n  |\n2 | 4x6\n  |  ^",
+        )
+    }
+
     #[test]
     pub fn test_zeros() {
         let mat = Mat::zeros((2, 3));
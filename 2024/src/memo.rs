@@ -0,0 +1,382 @@
+/// Caches for memoizing recursive functions
+use crate::*;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+/// Storage for the values computed by a [`Memo`].
+pub trait MemoBackend<K, V> {
+    fn get(&self, key: &K) -> Option<&V>;
+    fn insert(&mut self, key: K, value: V);
+    fn len(&self) -> usize;
+
+    fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl<K: Hash + Eq, V> MemoBackend<K, V> for HashMap<K, V> {
+    fn get(&self, key: &K) -> Option<&V> {
+        HashMap::get(self, key)
+    }
+
+    fn insert(&mut self, key: K, value: V) {
+        HashMap::insert(self, key, value);
+    }
+
+    fn len(&self) -> usize {
+        HashMap::len(self)
+    }
+}
+
+/// A backend for keys that are small non-negative integers, storing the values in a dense
+/// array instead of hashing the keys.
+#[derive(Debug, Clone)]
+pub struct DenseTable<V, const N: usize> {
+    shape: [usize; N],
+    values: Vec<Option<V>>,
+    len: usize,
+}
+
+impl<V, const N: usize> DenseTable<V, N> {
+    /// A table for keys `[k0, k1, ...]` with `k0 < shape[0]`, `k1 < shape[1]`, etc.
+    pub fn new(shape: [usize; N]) -> Self {
+        let size = shape.iter().product();
+        DenseTable {
+            shape,
+            values: std::iter::repeat_with(|| None).take(size).collect(),
+            len: 0,
+        }
+    }
+
+    fn offset(&self, key: &[usize; N]) -> usize {
+        key.iter().zip(&self.shape).fold(0, |offset, (&k, &size)| {
+            if k >= size {
+                panic!("key {:?} out of bounds for shape {:?}", key, self.shape);
+            }
+
+            offset * size + k
+        })
+    }
+}
+
+impl<V, const N: usize> MemoBackend<[usize; N], V> for DenseTable<V, N> {
+    fn get(&self, key: &[usize; N]) -> Option<&V> {
+        self.values[self.offset(key)].as_ref()
+    }
+
+    fn insert(&mut self, key: [usize; N], value: V) {
+        let offset = self.offset(&key);
+        if self.values[offset].replace(value).is_none() {
+            self.len += 1;
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.len
+    }
+}
+
+/// The number of lookups in a [`Memo`] that found a cached value (hits), and that did not (misses).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct MemoStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// A cache for the results of a recursive function. The function passes the cache along to its
+/// recursive calls, and wraps its body in [`Memo::get_or_compute`].
+///
+/// Keys may borrow from the input, such as `(&[char], &[usize])`, so that no copies of slices
+/// have to be made to look up a value.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// fn ways_to_make<'a>(memo: &mut Memo<(u64, &'a [u64]), u64>, amount: u64, coins: &'a [u64]) -> u64 {
+///     memo.get_or_compute((amount, coins), |memo| match coins {
+///         _ if amount == 0 => 1,
+///         [] => 0,
+///         [coin, rest @ ..] => {
+///             let with_coin = match amount.checked_sub(*coin) {
+///                 Some(left) => ways_to_make(memo, left, coins),
+///                 None => 0,
+///             };
+///             with_coin + ways_to_make(memo, amount, rest)
+///         }
+///     })
+/// }
+///
+/// let mut memo = Memo::new();
+/// assert_eq!(ways_to_make(&mut memo, 100, &[50, 25, 10, 5, 1]), 292);
+/// assert!(memo.stats().hits > 0);
+/// ```
+pub struct Memo<K, V, B = HashMap<K, V>> {
+    backend: B,
+    stats: MemoStats,
+    name: Option<&'static str>,
+    _entries: PhantomData<(K, V)>,
+}
+
+impl<K: Hash + Eq, V> Memo<K, V> {
+    /// A memo backed by a hash map.
+    pub fn new() -> Self {
+        Self::with_backend(HashMap::default())
+    }
+}
+
+impl<K: Hash + Eq, V> Default for Memo<K, V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<V, const N: usize> Memo<[usize; N], V, DenseTable<V, N>> {
+    /// A memo backed by a [`DenseTable`] of the given shape.
+    pub fn dense(shape: [usize; N]) -> Self {
+        Self::with_backend(DenseTable::new(shape))
+    }
+}
+
+impl<K, V, B: MemoBackend<K, V>> Memo<K, V, B> {
+    pub fn with_backend(backend: B) -> Self {
+        Memo {
+            backend,
+            stats: MemoStats::default(),
+            name: None,
+            _entries: PhantomData,
+        }
+    }
+
+    /// Gives the memo a name, under which its statistics are printed to stderr when it is dropped
+    /// in a debug build.
+    pub fn named(mut self, name: &'static str) -> Self {
+        self.name = Some(name);
+        self
+    }
+
+    pub fn stats(&self) -> MemoStats {
+        self.stats
+    }
+
+    /// The number of cached values.
+    pub fn len(&self) -> usize {
+        self.backend.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.backend.is_empty()
+    }
+
+    /// Looks up the cached value for `key`, counting a hit or a miss.
+    pub fn get(&mut self, key: &K) -> Option<V>
+    where
+        V: Clone,
+    {
+        let value = self.backend.get(key).cloned();
+        if value.is_some() {
+            self.stats.hits += 1;
+        } else {
+            self.stats.misses += 1;
+        }
+
+        value
+    }
+
+    pub fn insert(&mut self, key: K, value: V) {
+        self.backend.insert(key, value);
+    }
+
+    /// Returns the cached value for `key`, or computes and caches it. `compute` is given the memo
+    /// itself, so that it can make recursive calls with it.
+    pub fn get_or_compute(&mut self, key: K, compute: impl FnOnce(&mut Self) -> V) -> V
+    where
+        V: Clone,
+    {
+        if let Some(value) = self.get(&key) {
+            return value;
+        }
+
+        let value = compute(self);
+        self.backend.insert(key, value.clone());
+        value
+    }
+}
+
+impl<K, V, B> Drop for Memo<K, V, B> {
+    fn drop(&mut self) {
+        if let (true, Some(name)) = (cfg!(debug_assertions), self.name) {
+            let MemoStats { hits, misses } = self.stats;
+            let lookups = (hits + misses).max(1);
+            eprintln!(
+                "memo {}: {} hits, {} misses ({:.1}% hit rate)",
+                name,
+                hits,
+                misses,
+                100.0 * hits as f64 / lookups as f64
+            );
+        }
+    }
+}
+
+/// Defines a function whose results are cached per thread, keyed by its arguments. The arguments
+/// must be `Clone + Hash + Eq + 'static` and the result `Clone`; use a [`Memo`] for functions
+/// that take borrowed arguments.
+///
+/// The cache is not named, so it prints no statistics; use a [`Memo`] with [`Memo::named`] to
+/// see how often a cache hits.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// memoize! {
+///     fn fibonacci(n: u64) -> u64 {
+///         if n < 2 {
+///             n
+///         } else {
+///             fibonacci(n - 1) + fibonacci(n - 2)
+///         }
+///     }
+/// }
+///
+/// assert_eq!(fibonacci(90), 2880067194370816120);
+/// ```
+#[macro_export]
+macro_rules! memoize {
+    ($vis:vis fn $name:ident($($arg:ident: $ty:ty),* $(,)?) -> $ret:ty $body:block) => {
+        $vis fn $name($($arg: $ty),*) -> $ret {
+            ::std::thread_local! {
+                static MEMO: ::std::cell::RefCell<$crate::Memo<($($ty,)*), $ret>> =
+                    ::std::cell::RefCell::new($crate::Memo::new());
+            }
+
+            let key = ($(::std::clone::Clone::clone(&$arg),)*);
+            if let Some(value) = MEMO.with(|memo| memo.borrow_mut().get(&key)) {
+                return value;
+            }
+
+            fn compute($($arg: $ty),*) -> $ret $body
+
+            // The cache is not borrowed while computing, so that the body can recurse
+            let value = compute($($arg),*);
+            MEMO.with(|memo| memo.borrow_mut().insert(key, ::std::clone::Clone::clone(&value)));
+            value
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type ArrangementsMemo<'a> = Memo<(&'a [char], &'a [usize]), u64>;
+
+    fn arrangements<'a>(
+        memo: &mut ArrangementsMemo<'a>,
+        springs: &'a [char],
+        groups: &'a [usize],
+    ) -> u64 {
+        memo.get_or_compute((springs, groups), |memo| {
+            let Some(&group) = groups.first() else {
+                return (!springs.contains(&'#')) as u64;
+            };
+
+            let mut count = 0;
+            if springs.first().is_some_and(|&c| c != '#') {
+                count += arrangements(memo, &springs[1..], groups);
+            }
+
+            let fits = springs.len() >= group
+                && !springs[..group].contains(&'.')
+                && springs.get(group) != Some(&'#');
+            if fits {
+                let rest = &springs[(group + 1).min(springs.len())..];
+                count += arrangements(memo, rest, &groups[1..]);
+            }
+
+            count
+        })
+    }
+
+    const CONDITION_RECORDS: &str = "\
+???.### 1,1,3
+.??..??...?##. 1,1,3
+?#?#?#?#?#?#?#? 1,3,1,6
+????.#...#... 4,1,1
+????.######..#####. 1,6,5
+?###???????? 3,2,1
+";
+
+    #[test]
+    pub fn test_borrowed_slice_keys() {
+        let mut total = 0;
+        let mut unfolded_total = 0;
+        let mut hits = 0;
+        for line in CONDITION_RECORDS.lines() {
+            let (springs, groups) = line.split_once(' ').unwrap();
+            let springs = springs.chars().collect_vec();
+            let groups: Vec<usize> = parse_nums(groups).unwrap();
+            total += arrangements(&mut Memo::new(), &springs, &groups);
+
+            let unfolded_springs = vec![springs; 5].join(&'?');
+            let unfolded_groups = groups.repeat(5);
+            let mut memo = Memo::new();
+            unfolded_total += arrangements(&mut memo, &unfolded_springs, &unfolded_groups);
+            hits += memo.stats().hits;
+        }
+
+        assert_eq!(total, 21);
+        assert_eq!(unfolded_total, 525152);
+        assert!(hits > 0);
+    }
+
+    type DiracMemo = Memo<[usize; 4], (u64, u64), DenseTable<(u64, u64), 4>>;
+
+    /// The number of universes in which each player wins with Dirac dice
+    fn dirac_wins(memo: &mut DiracMemo, positions: [usize; 2], scores: [usize; 2]) -> (u64, u64) {
+        let key = [positions[0], positions[1], scores[0], scores[1]];
+        memo.get_or_compute(key, |memo| {
+            let mut wins = (0, 0);
+            for (roll, universes) in [(3, 1), (4, 3), (5, 6), (6, 7), (7, 6), (8, 3), (9, 1)] {
+                let position = (positions[0] + roll) % 10;
+                let score = scores[0] + position + 1;
+                if score >= 21 {
+                    wins.0 += universes;
+                } else {
+                    let (other, current) =
+                        dirac_wins(memo, [positions[1], position], [scores[1], score]);
+                    wins.0 += universes * current;
+                    wins.1 += universes * other;
+                }
+            }
+
+            wins
+        })
+    }
+
+    #[test]
+    pub fn test_dense_backend() {
+        let mut memo = Memo::dense([10, 10, 21, 21]);
+
+        let (wins_1, wins_2) = dirac_wins(&mut memo, [3, 7], [0, 0]);
+
+        assert_eq!(wins_1.max(wins_2), 444356092776315);
+        assert_eq!(memo.len() as u64, memo.stats().misses);
+    }
+
+    memoize! {
+        fn paths(rows: u64, cols: u64) -> u64 {
+            if rows == 0 || cols == 0 {
+                1
+            } else {
+                paths(rows - 1, cols) + paths(rows, cols - 1)
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_memoize_macro() {
+        assert_eq!(paths(16, 16), 601080390);
+    }
+}
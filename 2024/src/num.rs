@@ -0,0 +1,290 @@
+/// Number theory: gcd, lcm, modular arithmetic and the Chinese remainder theorem
+use num_traits::{PrimInt, Signed};
+
+/// The greatest common divisor of `a` and `b`, which is never negative. `gcd(0, 0)` is 0.
+///
+/// Panics if the result does not fit in `T`, which only happens for `gcd(T::MIN, 0)`,
+/// `gcd(0, T::MIN)` and `gcd(T::MIN, T::MIN)` of signed types.
+pub fn gcd<T: PrimInt>(a: T, b: T) -> T {
+    let (mut a, mut b) = (a, b);
+    while !b.is_zero() {
+        // `T::MIN % -1` overflows, even though the remainder is 0
+        let remainder = match a.checked_div(&b) {
+            Some(_) => a % b,
+            None => T::zero(),
+        };
+        (a, b) = (b, remainder);
+    }
+
+    if a < T::zero() {
+        T::zero()
+            .checked_sub(&a)
+            .expect("gcd does not fit in the integer type")
+    } else {
+        a
+    }
+}
+
+/// The least common multiple of `a` and `b`, which is never negative. It is 0 if either is 0.
+///
+/// Panics if the result does not fit in `T`.
+pub fn lcm<T: PrimInt>(a: T, b: T) -> T {
+    if a.is_zero() || b.is_zero() {
+        return T::zero();
+    }
+
+    let lcm = (a / gcd(a, b)).checked_mul(&b);
+    let lcm = match lcm {
+        Some(lcm) if lcm < T::zero() => T::zero().checked_sub(&lcm),
+        lcm => lcm,
+    };
+
+    lcm.expect("lcm does not fit in the integer type")
+}
+
+/// The least common multiple of all numbers in `values`, or 1 if there are none.
+///
+/// Panics if the result does not fit in `T`.
+pub fn lcm_all<T: PrimInt>(values: impl IntoIterator<Item = T>) -> T {
+    values.into_iter().fold(T::one(), lcm)
+}
+
+/// Returns `(g, x, y)` such that `g = gcd(a, b)` and `a * x + b * y = g`.
+pub fn extended_gcd<T: PrimInt + Signed>(a: T, b: T) -> (T, T, T) {
+    let (mut r0, mut r1) = (a, b);
+    let (mut x0, mut x1) = (T::one(), T::zero());
+    let (mut y0, mut y1) = (T::zero(), T::one());
+    while !r1.is_zero() {
+        let q = r0 / r1;
+        (r0, r1) = (r1, r0 - q * r1);
+        (x0, x1) = (x1, x0 - q * x1);
+        (y0, y1) = (y1, y0 - q * y1);
+    }
+
+    if r0 < T::zero() {
+        (-r0, -x0, -y0)
+    } else {
+        (r0, x0, y0)
+    }
+}
+
+/// The `x` in `0..m` with `a * x ≡ 1 (mod m)`, if `a` and `m` are coprime. Returns `None` if `m`
+/// is not positive.
+pub fn mod_inverse<T: PrimInt + Signed>(a: T, m: T) -> Option<T> {
+    if m <= T::zero() {
+        return None;
+    }
+
+    let (g, x, _) = extended_gcd(a % m, m);
+
+    // `x` is in `-m..m`, so adding `m` to a negative remainder cannot overflow
+    g.is_one().then(|| {
+        let x = x % m;
+        if x < T::zero() {
+            x + m
+        } else {
+            x
+        }
+    })
+}
+
+/// `base` to the power `exp`, modulo `m`. Intermediate products are computed with 128 bits, so
+/// `m` may be as large as `2^64`.
+pub fn pow_mod<T: PrimInt>(base: T, exp: u64, m: T) -> T {
+    let m = m.to_u128().expect("modulus must be positive");
+    assert!(m > 0, "modulus must be positive");
+
+    let base = base.to_i128().expect("base out of range");
+    let mut base = base.rem_euclid(m as i128) as u128;
+    let mut exp = exp;
+    let mut result = 1 % m;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+
+    T::from(result).unwrap()
+}
+
+/// Solves the system of congruences `x ≡ residue (mod modulus)`, returning `(x, lcm)` where `lcm`
+/// is the least common multiple of the moduli and `x` is the unique solution in `0..lcm`.
+///
+/// The moduli do not need to be coprime. Returns `None` if the congruences are inconsistent, or
+/// if the solution does not fit in `T`.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+/// assert_eq!(crt(&[(1, 4), (3, 6)]), Some((9, 12)));
+/// assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+/// ```
+pub fn crt<T: PrimInt + Signed>(congruences: &[(T, T)]) -> Option<(T, T)> {
+    let mut x: i128 = 0;
+    let mut modulus: i128 = 1;
+    for &(residue, m) in congruences {
+        let residue = residue.to_i128()?;
+        let m = m.to_i128()?.checked_abs()?;
+        if m == 0 {
+            return None;
+        }
+
+        // Find k such that x + modulus * k ≡ residue (mod m)
+        let (g, inverse, _) = extended_gcd(modulus, m);
+        let diff = residue.checked_sub(x)?;
+        if diff % g != 0 {
+            return None;
+        }
+
+        let m_g = m / g;
+        let k = mul_mod((diff / g).rem_euclid(m_g), inverse.rem_euclid(m_g), m_g);
+        x = x.checked_add(modulus.checked_mul(k)?)?;
+        modulus = modulus.checked_mul(m_g)?;
+        x = x.rem_euclid(modulus);
+    }
+
+    Some((T::from(x)?, T::from(modulus)?))
+}
+
+/// `a * b % m` for `a` and `b` in `0..m`, without overflowing.
+fn mul_mod(a: i128, b: i128, m: i128) -> i128 {
+    if let Some(product) = a.checked_mul(b) {
+        return product % m;
+    }
+
+    // Double and add, keeping every intermediate value below `2 * m`
+    let (mut a, mut b) = (a, b);
+    let mut result = 0;
+    while b > 0 {
+        if b & 1 == 1 {
+            result = (result + a) % m;
+        }
+        a = (a + a) % m;
+        b >>= 1;
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn brute_force_gcd(a: i64, b: i64) -> i64 {
+        (1..=a.abs().max(b.abs()))
+            .rev()
+            .find(|d| a % d == 0 && b % d == 0)
+            .unwrap_or(0)
+    }
+
+    #[test]
+    pub fn test_gcd_and_lcm() {
+        for a in -30i64..=30 {
+            for b in -30i64..=30 {
+                let g = gcd(a, b);
+                assert_eq!(g, brute_force_gcd(a, b), "gcd({}, {})", a, b);
+
+                let l = lcm(a, b);
+                let expected = (1..=(a * b).abs())
+                    .find(|m| m % a == 0 && m % b == 0)
+                    .unwrap_or(0);
+                assert_eq!(l, expected, "lcm({}, {})", a, b);
+
+                let (eg, x, y) = extended_gcd(a, b);
+                assert_eq!(eg, g);
+                assert_eq!(a * x + b * y, g);
+            }
+        }
+
+        for a in 0u8..=60 {
+            for b in 0u8..=60 {
+                assert_eq!(gcd(a, b) as i64, brute_force_gcd(a as i64, b as i64));
+            }
+        }
+
+        assert_eq!(lcm_all([2u64, 3, 4, 5]), 60);
+        assert_eq!(gcd(i64::MIN, -1), 1);
+        assert_eq!(gcd(i64::MIN, 6), 2);
+    }
+
+    #[test]
+    #[should_panic(expected = "gcd does not fit in the integer type")]
+    pub fn test_gcd_overflow() {
+        gcd(i64::MIN, 0);
+    }
+
+    #[test]
+    #[should_panic(expected = "lcm does not fit in the integer type")]
+    pub fn test_lcm_overflow() {
+        lcm(i64::MAX, 2);
+    }
+
+    #[test]
+    pub fn test_mod_inverse_and_pow_mod() {
+        for m in 1i64..=40 {
+            for a in -40i64..=40 {
+                let expected = (0..m).find(|x| (a * x).rem_euclid(m) == 1 % m);
+                assert_eq!(mod_inverse(a, m), expected, "{}^-1 mod {}", a, m);
+
+                for exp in 0..10 {
+                    let expected = (0..exp).fold(1 % m, |p, _| (p * a).rem_euclid(m));
+                    assert_eq!(pow_mod(a, exp as u64, m), expected);
+                }
+            }
+        }
+
+        assert_eq!(mod_inverse(3, 0), None);
+        assert_eq!(mod_inverse(3, -7), None);
+        assert_eq!(mod_inverse(2, i64::MAX), Some(1 << 62));
+        assert_eq!(mod_inverse(-2, i64::MAX), Some(i64::MAX - (1 << 62)));
+
+        let m = (1u64 << 61) - 1;
+        assert_eq!(pow_mod(3u64, m - 1, m), 1);
+    }
+
+    #[test]
+    pub fn test_crt() {
+        for m1 in 1i64..=12 {
+            for m2 in 1i64..=12 {
+                for a1 in -3..m1 {
+                    for a2 in 0..m2 {
+                        let l = lcm(m1, m2);
+                        let expected = (0..l)
+                            .find(|x| (x - a1).rem_euclid(m1) == 0 && (x - a2) % m2 == 0)
+                            .map(|x| (x, l));
+
+                        assert_eq!(crt(&[(a1, m1), (a2, m2)]), expected);
+                    }
+                }
+            }
+        }
+
+        assert_eq!(crt::<i64>(&[]), Some((0, 1)));
+        assert_eq!(crt(&[(0, i64::MIN)]), None);
+        assert_eq!(crt(&[(0, 0)]), None::<(i64, i64)>);
+    }
+
+    #[test]
+    pub fn test_crt_large_moduli() {
+        let primes = [1_000_000_007i64, 998_244_353, 1_000_000_009];
+        let congruences = primes.map(|p| (p - 1, p));
+
+        // The lcm is about 10^27, which does not fit in an i64
+        assert_eq!(crt(&congruences), None);
+
+        let congruences = congruences.map(|(r, m)| (r as i128, m as i128));
+        let (x, lcm) = crt(&congruences).unwrap();
+        assert_eq!(lcm, primes.iter().map(|&p| p as i128).product::<i128>());
+        assert_eq!(x, lcm - 1);
+
+        // Moduli close to 2^63 overflow i128 in the third step
+        let primes = [i64::MAX as i128, (1i128 << 61) - 1, (1i128 << 31) - 1];
+        let (x, _) = crt(&[(1, primes[0]), (1, primes[1])]).unwrap();
+        assert_eq!(x, 1);
+        assert_eq!(crt(&primes.map(|p| (1, p))), None);
+    }
+}
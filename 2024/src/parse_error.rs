@@ -0,0 +1,149 @@
+/// Parse errors that point at the offending part of the input
+use std::fmt::{Display, Formatter};
+
+/// An error produced by the parsing helpers, showing where in the input parsing failed.
+///
+/// It is a regular [`std::error::Error`], so it can be given more context with eyre's
+/// `wrap_err`, and retrieved again with `downcast_ref`.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let err = ParseError::at_offset("123\n4x6", 5, "a digit", "'x'");
+///
+/// assert_eq!(err.line_no, Some(2));
+/// assert_eq!(err.column, 2);
+/// assert_eq!(
+///     err.to_string(),
+///     "expected a digit, found 'x' at line 2, column 2 (byte offset 5)\n  |\n2 | 4x6\n  |  ^",
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ParseError {
+    /// Line number in the input, starting at 1, if known.
+    pub line_no: Option<usize>,
+    /// Column (in characters) within the line, starting at 1. A column of 0 is shown as
+    /// pointing at the start of the line.
+    pub column: usize,
+    /// Byte offset in the input, if known.
+    pub offset: Option<usize>,
+    /// The line on which parsing failed.
+    pub line: String,
+    pub expected: String,
+    pub found: String,
+}
+
+impl ParseError {
+    pub fn new(
+        line: &str,
+        line_no: Option<usize>,
+        column: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        ParseError {
+            line_no,
+            column,
+            offset: None,
+            line: line.trim_end_matches(['\r', '\n']).to_string(),
+            expected: expected.into(),
+            found: found.into(),
+        }
+    }
+
+    /// Creates an error pointing at byte `offset` of `input`.
+    pub fn at_offset(
+        input: &str,
+        offset: usize,
+        expected: impl Into<String>,
+        found: impl Into<String>,
+    ) -> Self {
+        let offset = offset.min(input.len());
+        let line_start = input[..offset].rfind('\n').map_or(0, |i| i + 1);
+        let line_end = input[offset..]
+            .find('\n')
+            .map_or(input.len(), |i| offset + i);
+        let line_no = input[..line_start].matches('\n').count() + 1;
+        let column = input[line_start..offset].chars().count() + 1;
+
+        ParseError {
+            offset: Some(offset),
+            ..Self::new(
+                &input[line_start..line_end],
+                Some(line_no),
+                column,
+                expected,
+                found,
+            )
+        }
+    }
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "expected {}, found {}", self.expected, self.found)?;
+
+        let gutter = match self.line_no {
+            Some(line_no) => {
+                write!(f, " at line {}, column {}", line_no, self.column)?;
+                line_no.to_string()
+            }
+            None => {
+                write!(f, " at column {}", self.column)?;
+                String::new()
+            }
+        };
+
+        if let Some(offset) = self.offset {
+            write!(f, " (byte offset {})", offset)?;
+        }
+
+        let pad = " ".repeat(gutter.len());
+        write!(
+            f,
+            "\n{} |\n{} | {}\n{} | {}^",
+            pad,
+            gutter,
+            self.line,
+            pad,
+            " ".repeat(self.column.saturating_sub(1))
+        )
+    }
+}
+
+impl std::error::Error for ParseError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    pub fn test_display_without_line_number() {
+        let err = ParseError::new("move 3 frm 1", None, 8, "\"from\"", "\"frm 1\"");
+
+        assert_eq!(
+            err.to_string(),
+            "expected \"from\", found \"frm 1\" at column 8\n |\n | move 3 frm 1\n |        ^"
+        );
+    }
+
+    #[test]
+    pub fn test_display_column_zero() {
+        let err = ParseError::new("abc", Some(1), 0, "a number", "\"abc\"");
+
+        assert_eq!(
+            err.to_string(),
+            "expected a number, found \"abc\" at line 1, column 0\n  |\n1 | abc\n  | ^"
+        );
+    }
+
+    #[test]
+    pub fn test_at_offset_at_end_of_input() {
+        let err = ParseError::at_offset("ab\ncd\n", 6, "more input", "end of input");
+
+        assert_eq!(err.line_no, Some(3));
+        assert_eq!(err.column, 1);
+        assert_eq!(err.line, "");
+    }
+}
@@ -1,53 +1,598 @@
-use eyre::eyre;
-use regex::Regex;
+use crate::ParseError;
+use eyre::{eyre, WrapErr};
+use itertools::Itertools;
+use num_traits::PrimInt;
 /// Parsing utilities
-use std::sync::LazyLock;
+use std::fmt::Display;
+use std::str::FromStr;
 
-static NUM_REGEX: LazyLock<Regex> = LazyLock::new(|| Regex::new("(-?\\d+)").unwrap());
+/// Integer types that can be extracted with [`parse_nums`] and friends.
+pub trait ParseNum: PrimInt + FromStr<Err: Display> {}
 
-pub fn parse_nums(str: &str) -> crate::Result<Vec<i64>> {
-    let nums = NUM_REGEX
-        .captures_iter(str)
-        .map(|c| c.get(1).unwrap())
-        .map(|c| c.as_str().parse::<i64>().unwrap())
-        .collect::<Vec<i64>>();
+impl<T: PrimInt + FromStr<Err: Display>> ParseNum for T {}
 
-    Ok(nums)
+/// Extracts all integers from `str`, ignoring anything in between.
+///
+/// For signed types, a `-` directly in front of a number is a minus sign, unless it is itself
+/// preceded by a digit: `"x=-3"` gives `-3`, but `"1-2"` gives `1, 2`. For unsigned types,
+/// dashes are always ignored. Fails if a number does not fit in `T`.
+pub fn parse_nums<T: ParseNum>(str: &str) -> crate::Result<Vec<T>> {
+    let signed = T::min_value() < T::zero();
+    extract_nums(str, signed)
+}
+
+/// Like [`parse_nums`], but never treats a `-` as a minus sign, so that e.g. ranges like
+/// `"2-4,6-8"` can be read into signed types.
+pub fn parse_unsigned_nums<T: ParseNum>(str: &str) -> crate::Result<Vec<T>> {
+    extract_nums(str, false)
+}
+
+/// Extracts exactly `N` integers from `str`.
+pub fn parse_n<T: ParseNum, const N: usize>(str: &str) -> crate::Result<[T; N]> {
+    let nums: Vec<T> = parse_nums(str)?;
+
+    nums.try_into()
+        .map_err(|nums: Vec<T>| eyre!("expected {} numbers, but found {} numbers", N, nums.len()))
 }
 
 pub fn parse_num_pair(str: &str) -> crate::Result<(i64, i64)> {
-    let nums = parse_nums(str)?;
-    if nums.len() != 2 {
-        return Err(eyre!(
-            "expected 2 numbers, but found {} numbers",
-            nums.len()
-        ));
-    }
+    let [a, b] = parse_n(str)?;
 
-    Ok((nums[0], nums[1]))
+    Ok((a, b))
 }
 
 pub fn parse_num_triple(str: &str) -> crate::Result<(i64, i64, i64)> {
-    let nums = parse_nums(str)?;
-    if nums.len() != 3 {
-        return Err(eyre!(
-            "expected 3 numbers, but found {} numbers",
-            nums.len()
-        ));
+    let [a, b, c] = parse_n(str)?;
+
+    Ok((a, b, c))
+}
+
+fn extract_nums<T: ParseNum>(str: &str, allow_sign: bool) -> crate::Result<Vec<T>> {
+    let bytes = str.as_bytes();
+
+    let mut nums = vec![];
+    let mut i = 0;
+    while i < bytes.len() {
+        if !bytes[i].is_ascii_digit() {
+            i += 1;
+            continue;
+        }
+
+        let mut start = i;
+        while i < bytes.len() && bytes[i].is_ascii_digit() {
+            i += 1;
+        }
+
+        let is_negative = allow_sign
+            && start > 0
+            && bytes[start - 1] == b'-'
+            && (start < 2 || !bytes[start - 2].is_ascii_digit());
+        if is_negative {
+            start -= 1;
+        }
+
+        let num = str[start..i].parse::<T>().map_err(|e| {
+            ParseError::at_offset(
+                str,
+                start,
+                std::any::type_name::<T>(),
+                format!("{:?} ({})", &str[start..i], e),
+            )
+        })?;
+        nums.push(num);
+    }
+
+    Ok(nums)
+}
+
+/// A value that can be read from a `{}` capture of [`scan`].
+pub trait FromScan<'a>: Sized {
+    fn from_scan(field: &'a str) -> crate::Result<Self>;
+}
+
+macro_rules! from_scan_impl {
+    ($($t:ty),*) => {
+        $(
+            impl FromScan<'_> for $t {
+                fn from_scan(field: &str) -> crate::Result<Self> {
+                    field.trim().parse::<$t>().map_err(|e| eyre!("{}", e))
+                }
+            }
+        )*
+    };
+}
+
+from_scan_impl!(
+    i8, i16, i32, i64, i128, isize, u8, u16, u32, u64, u128, usize, f32, f64, char, bool, String
+);
+
+impl<'a> FromScan<'a> for &'a str {
+    fn from_scan(field: &'a str) -> crate::Result<Self> {
+        Ok(field)
+    }
+}
+
+/// Lists are split on commas and whitespace, e.g. `"DD, II, BB"`.
+impl<'a, T: FromScan<'a>> FromScan<'a> for Vec<T> {
+    fn from_scan(field: &'a str) -> crate::Result<Self> {
+        field
+            .split(|c: char| c == ',' || c.is_whitespace())
+            .filter(|item| !item.is_empty())
+            .map(T::from_scan)
+            .collect()
+    }
+}
+
+/// The tuple of values produced by [`scan`], one per `{}` capture.
+pub trait ScanFields<'a>: Sized {
+    fn from_fields(line: &str, fields: &[(usize, &'a str)]) -> crate::Result<Self>;
+}
+
+macro_rules! scan_fields_impl {
+    ($n:expr; $($t:ident $i:tt),+) => {
+        impl<'a, $($t: FromScan<'a>),+> ScanFields<'a> for ($($t,)+) {
+            fn from_fields(line: &str, fields: &[(usize, &'a str)]) -> crate::Result<Self> {
+                if fields.len() != $n {
+                    return Err(eyre!(
+                        "pattern has {} captures, but {} values were requested",
+                        fields.len(),
+                        $n
+                    ));
+                }
+
+                Ok(($(
+                    $t::from_scan(fields[$i].1).map_err(|e| {
+                        ParseError::new(
+                            line,
+                            None,
+                            fields[$i].0,
+                            std::any::type_name::<$t>(),
+                            format!("{:?} ({})", fields[$i].1, e),
+                        )
+                    })?,
+                )+))
+            }
+        }
+    };
+}
+
+scan_fields_impl!(1; A 0);
+scan_fields_impl!(2; A 0, B 1);
+scan_fields_impl!(3; A 0, B 1, C 2);
+scan_fields_impl!(4; A 0, B 1, C 2, D 3);
+scan_fields_impl!(5; A 0, B 1, C 2, D 3, E 4);
+scan_fields_impl!(6; A 0, B 1, C 2, D 3, E 4, F 5);
+
+/// Matches `input` against `pattern`, in which every `{}` captures text up to the next literal
+/// part of the pattern (or the end of the line), and parses the captures into a tuple.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let line = "Valve BB has flow rate=13; tunnels lead to valves CC, AA";
+/// let (valve, rate, tunnels): (&str, u32, Vec<&str>) =
+///     scan!(line, "Valve {} has flow rate={}; tunnels lead to valves {}").unwrap();
+///
+/// assert_eq!(valve, "BB");
+/// assert_eq!(rate, 13);
+/// assert_eq!(tunnels, vec!["CC", "AA"]);
+/// ```
+pub fn scan<'a, T: ScanFields<'a>>(input: &'a str, pattern: &str) -> crate::Result<T> {
+    let input = input.trim_end_matches(['\r', '\n']);
+    let column = |rest: &str| input[..input.len() - rest.len()].chars().count() + 1;
+
+    let mut literals = pattern.split("{}");
+    let mut rest = input;
+    let mut fields = vec![];
+
+    let first = literals.next().unwrap_or_default();
+    rest = rest.strip_prefix(first).ok_or_else(|| {
+        let matching = first.chars().zip(rest.chars()).take_while(|(a, b)| a == b);
+        ParseError::new(
+            input,
+            None,
+            column(rest) + matching.count(),
+            format!("{:?}", first),
+            format!("{:?}", rest),
+        )
+    })?;
+
+    let literals = literals.collect_vec();
+    for (i, &literal) in literals.iter().enumerate() {
+        let is_last = i + 1 == literals.len();
+        let capture_len = if literal.is_empty() && is_last {
+            rest.len()
+        } else if literal.is_empty() {
+            return Err(eyre!("pattern {:?} has two adjacent captures", pattern));
+        } else {
+            rest.find(literal).ok_or_else(|| {
+                ParseError::new(
+                    input,
+                    None,
+                    column(rest),
+                    format!("{:?}", literal),
+                    format!("{:?}", rest),
+                )
+            })?
+        };
+
+        fields.push((column(rest), &rest[..capture_len]));
+        rest = &rest[capture_len + literal.len()..];
+    }
+
+    if !rest.is_empty() {
+        let found = format!("{:?}", rest);
+        return Err(ParseError::new(input, None, column(rest), "end of line", found).into());
+    }
+
+    T::from_fields(input, &fields)
+}
+
+/// Shorthand for [`scan`]. The types of the captures can be given after the pattern, otherwise
+/// they are inferred.
+#[macro_export]
+macro_rules! scan {
+    ($input:expr, $pattern:expr $(,)?) => {
+        $crate::scan($input, $pattern)
+    };
+    ($input:expr, $pattern:expr, $($t:ty),+ $(,)?) => {
+        $crate::scan::<($($t,)+)>($input, $pattern)
+    };
+}
+
+/// A block of consecutive non-blank lines, as produced by [`sections`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section<'a> {
+    /// Position of the section in the input, starting at 0.
+    pub index: usize,
+    /// Line number (starting at 1) of the first line of the section in the input.
+    pub line_no: usize,
+    /// The text before the colon of a `"name:"` header, if parsed with [`labeled_sections`].
+    pub label: Option<&'a str>,
+    /// Lines without line endings, otherwise as they appear in the input.
+    pub lines: Vec<&'a str>,
+}
+
+impl Section<'_> {
+    /// Removes trailing whitespace from every line. Sections keep their lines verbatim by
+    /// default, since trailing spaces matter in fixed-column inputs.
+    pub fn trim_end(mut self) -> Self {
+        for line in &mut self.lines {
+            *line = line.trim_end();
+        }
+
+        self
+    }
+
+    /// The lines of the section joined with `\n`.
+    pub fn text(&self) -> String {
+        self.lines.join("\n")
+    }
+
+    /// Runs `parser` on this section, adding the section index, label and line number to any
+    /// error it returns.
+    pub fn parse<T>(&self, parser: impl FnOnce(&Self) -> crate::Result<T>) -> crate::Result<T> {
+        parser(self).wrap_err_with(|| match self.label {
+            Some(label) => format!(
+                "while parsing section {} ({:?}) starting at line {}",
+                self.index + 1,
+                label,
+                self.line_no
+            ),
+            None => format!(
+                "while parsing section {} starting at line {}",
+                self.index + 1,
+                self.line_no
+            ),
+        })
+    }
+}
+
+/// Splits `input` into sections separated by one or more blank lines. Both `\n` and `\r\n` line
+/// endings are accepted, and lines consisting of only whitespace count as blank.
+pub fn sections(input: &str) -> Vec<Section<'_>> {
+    let mut sections: Vec<Section> = vec![];
+    let mut in_section = false;
+    for (line_idx, line) in input.lines().enumerate() {
+        if line.trim().is_empty() {
+            in_section = false;
+            continue;
+        }
+
+        if !in_section {
+            sections.push(Section {
+                index: sections.len(),
+                line_no: line_idx + 1,
+                label: None,
+                lines: vec![],
+            });
+            in_section = true;
+        }
+
+        sections.last_mut().unwrap().lines.push(line);
     }
 
-    Ok((nums[0], nums[1], nums[2]))
+    sections
+}
+
+/// Like [`sections`], but requires every section to start with a `"name:"` header, such as
+/// `"seed-to-soil map:"`. Anything after the colon on the header line (as in `"seeds: 79 14"`)
+/// becomes the first line of the section.
+pub fn labeled_sections(input: &str) -> crate::Result<Vec<Section<'_>>> {
+    sections(input)
+        .into_iter()
+        .map(|mut section| {
+            let header = section.lines[0];
+            let (label, rest) = header.split_once(':').ok_or_else(|| {
+                ParseError::new(
+                    header,
+                    Some(section.line_no),
+                    header.chars().count() + 1,
+                    format!("a \"name:\" header for section {}", section.index + 1),
+                    format!("{:?}", header),
+                )
+            })?;
+
+            section.label = Some(label.trim());
+            if rest.trim().is_empty() {
+                section.lines.remove(0);
+            } else {
+                section.lines[0] = rest.trim();
+            }
+
+            Ok(section)
+        })
+        .collect()
+}
+
+/// Splits `input` into exactly `N` sections.
+pub fn sections_n<const N: usize>(input: &str) -> crate::Result<[Section<'_>; N]> {
+    sections(input)
+        .try_into()
+        .map_err(|sections: Vec<Section>| {
+            let expected = format!("{} sections", N);
+            let found = format!("{} sections", sections.len());
+            match sections.get(N) {
+                // Point at the first section too many
+                Some(extra) => {
+                    ParseError::new(extra.lines[0], Some(extra.line_no), 1, expected, found).into()
+                }
+                None => ParseError::at_offset(input, input.len(), expected, found).into(),
+            }
+        })
+}
+
+/// Splits `input` into lines, returning each line with the byte offset at which it starts in
+/// `input`. Accepts both `\n` and `\r\n` line endings and ignores trailing empty lines.
+pub(crate) fn text_lines(input: &str) -> Vec<(usize, &str)> {
+    let mut lines = Vec::new();
+    let mut offset = 0;
+    for line in input.split('\n') {
+        lines.push((offset, line.strip_suffix('\r').unwrap_or(line)));
+        offset += line.len() + 1;
+    }
+
+    while lines.last().is_some_and(|(_, line)| line.is_empty()) {
+        lines.pop();
+    }
+
+    lines
+}
+
+/// Splits grid-shaped text into rows like [`text_lines`], but rejects rows whose length differs
+/// from the first row. Rows of only spaces are kept, as they are valid rows of some maps, but
+/// trailing whitespace-only lines of a different length are ignored (see
+/// [`is_trailing_whitespace`]).
+pub(crate) fn grid_lines(input: &str) -> crate::Result<Vec<(usize, &str)>> {
+    let mut lines = text_lines(input);
+
+    let ncols = match lines.first() {
+        Some((_, first)) => first.chars().count(),
+        None => return Err(eyre!("empty input")),
+    };
+
+    while lines.len() > 1 && is_trailing_whitespace(lines.last().unwrap().1.as_bytes(), ncols) {
+        lines.pop();
+    }
+
+    for (line_no, (_, line)) in lines.iter().enumerate() {
+        let len = line.chars().count();
+        if len != ncols {
+            return Err(ParseError::new(
+                line,
+                Some(line_no + 1),
+                len.min(ncols) + 1,
+                format!("{} columns", ncols),
+                format!("{} columns", len),
+            )
+            .into());
+        }
+    }
+
+    Ok(lines)
+}
+
+/// Whether the last line of a grid is left-over whitespace (such as indentation before the end of
+/// a string literal) rather than a row: a row of spaces must be as wide as the grid.
+pub(crate) fn is_trailing_whitespace(line: &[u8], ncols: usize) -> bool {
+    line.len() != ncols && line.iter().all(|b| b.is_ascii_whitespace())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::parse_nums;
+    use super::*;
 
     #[test]
     pub fn test_parse_nums() {
         assert_eq!(
-            parse_nums("1 2s3\n4#5 -6").unwrap(),
+            parse_nums::<i64>("1 2s3\n4#5 -6").unwrap(),
             vec![1, 2, 3, 4, 5, -6],
         );
     }
+
+    #[test]
+    pub fn test_parse_nums_dashes() {
+        assert_eq!(
+            parse_nums::<i32>("x=-3, 2-4,-6-8").unwrap(),
+            vec![-3, 2, 4, -6, 8]
+        );
+        assert_eq!(parse_nums::<u8>("x=-3, 2-4").unwrap(), vec![3, 2, 4]);
+        assert_eq!(
+            parse_unsigned_nums::<i64>("x=-3, 2-4").unwrap(),
+            vec![3, 2, 4]
+        );
+    }
+
+    #[test]
+    pub fn test_parse_nums_overflow() {
+        let err = parse_nums::<u8>("1\n2 256").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+
+        assert_eq!(err.line_no, Some(2));
+        assert_eq!(err.column, 3);
+        assert_eq!(err.offset, Some(4));
+        assert_eq!(err.expected, "u8");
+        assert_eq!(
+            err.found,
+            "\"256\" (number too large to fit in target type)"
+        );
+    }
+
+    #[test]
+    pub fn test_parse_n() {
+        assert_eq!(parse_n::<u64, 4>("1,2 3-4").unwrap(), [1, 2, 3, 4]);
+        assert!(parse_n::<u64, 3>("1,2 3-4").is_err());
+    }
+
+    #[test]
+    pub fn test_grid_lines_normalises_line_endings() {
+        assert_eq!(
+            grid_lines("ab\r\ncd\r\n\r\n").unwrap(),
+            vec![(0, "ab"), (4, "cd")],
+        );
+    }
+
+    #[test]
+    pub fn test_grid_lines_keeps_rows_of_spaces() {
+        assert_eq!(
+            grid_lines("#.#\n   \n   \n\n").unwrap(),
+            vec![(0, "#.#"), (4, "   "), (8, "   ")],
+        );
+        assert_eq!(
+            grid_lines("#.#\n...\n  ").unwrap(),
+            vec![(0, "#.#"), (4, "...")]
+        );
+    }
+
+    #[test]
+    pub fn test_grid_lines_rejects_ragged_rows() {
+        let err = grid_lines("abc\nabc\nab\n").unwrap_err();
+
+        assert_eq!(
+            err.to_string(),
+            "expected 3 columns, found 2 columns at line 3, column 3\n  |\n3 | ab\n  |   ^",
+        );
+    }
+
+    #[test]
+    pub fn test_sections() {
+        let input = "a\r\nb\r\n  \r\n\r\nc \n\n";
+
+        let sections = sections(input);
+
+        assert_eq!(sections.len(), 2);
+        assert_eq!(sections[0].lines, vec!["a", "b"]);
+        assert_eq!(sections[1].lines, vec!["c "]);
+        assert_eq!(sections[1].line_no, 5);
+        assert_eq!(sections[1].clone().trim_end().lines, vec!["c"]);
+    }
+
+    #[test]
+    pub fn test_sections_n_reports_position() {
+        let err = sections_n::<1>("a\n\nb\nc\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line_no, err.column), (Some(3), 1));
+        assert_eq!(err.found, "2 sections");
+
+        let err = sections_n::<3>("a\n\nb\n").unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!((err.line_no, err.column), (Some(4), 1));
+        assert_eq!(err.expected, "3 sections");
+    }
+
+    #[test]
+    pub fn test_sections_keep_fixed_columns() {
+        let input = "    [D]    \n[N] [C]    \n[Z] [M] [P]\n 1   2   3 \n\nmove 1 from 2 to 1\n";
+
+        let [stacks, moves] = sections_n(input).unwrap();
+
+        assert!(stacks.lines.iter().all(|line| line.len() == 11));
+        assert_eq!(stacks.lines[0], "    [D]    ");
+        assert_eq!(moves.lines, vec!["move 1 from 2 to 1"]);
+    }
+
+    #[test]
+    pub fn test_labeled_sections() {
+        let input = "seeds: 79 14\n\nseed-to-soil map:\n50 98 2\n52 50 48\n\nbroken\n";
+
+        let err = labeled_sections(input).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.line_no, Some(7));
+        assert_eq!(err.column, 7);
+        assert_eq!(err.expected, "a \"name:\" header for section 3");
+        assert_eq!(err.found, "\"broken\"");
+
+        let sections = labeled_sections(input.trim_end_matches("broken\n")).unwrap();
+        assert_eq!(sections[0].label, Some("seeds"));
+        assert_eq!(sections[0].lines, vec!["79 14"]);
+        assert_eq!(sections[1].label, Some("seed-to-soil map"));
+        assert_eq!(sections[1].text(), "50 98 2\n52 50 48");
+
+        let err = sections[1]
+            .parse(|s| parse_n::<i64, 2>(&s.text()))
+            .unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "while parsing section 2 (\"seed-to-soil map\") starting at line 3"
+        );
+    }
+
+    #[test]
+    pub fn test_scan() {
+        let (a, b, c) = scan!(
+            "move 3 from 1 to 2",
+            "move {} from {} to {}",
+            u8,
+            i64,
+            usize
+        )
+        .unwrap();
+        assert_eq!((a, b, c), (3, 1, 2));
+
+        let (name, values): (String, Vec<i64>) = scan!("Sue 12: 1, 2, -3", "Sue {}: {}").unwrap();
+        assert_eq!(name, "12");
+        assert_eq!(values, vec![1, 2, -3]);
+    }
+
+    #[test]
+    pub fn test_scan_reports_columns() {
+        let err = scan!("move 3 from 1 in 2", "move {} from {} to {}", u8, u8, u8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected \" to \", found \"1 in 2\" at column 13\n |\n | move 3 from 1 in 2\n |             ^"
+        );
+
+        let err = scan!("mvoe 3 from 1 to 2", "move {} from {} to {}", u8, u8, u8).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "expected \"move \", found \"mvoe 3 from 1 to 2\" at column 2\n |\n | mvoe 3 from 1 to 2\n |  ^"
+        );
+
+        let err = scan!("move x from 1 to 2", "move {} from {} to {}", u8, u8, u8).unwrap_err();
+        let err = err.downcast::<ParseError>().unwrap();
+        assert_eq!(err.column, 6);
+        assert_eq!(err.expected, "u8");
+        assert_eq!(err.found, "\"x\" (invalid digit found in string)");
+    }
 }
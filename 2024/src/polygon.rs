@@ -0,0 +1,201 @@
+/// Areas and lattice point counts of polygons
+use crate::*;
+
+/// A closed polygon with vertices on integer coordinates. Coordinates are stored as `i128`, so
+/// that areas of polygons with very long edges do not overflow.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let steps = [(Dir::Right, 6), (Dir::Down, 5), (Dir::Left, 6), (Dir::Up, 5)];
+/// let polygon = Polygon::from_steps((0, 0), steps).unwrap();
+///
+/// assert_eq!(polygon.area(), 30);
+/// assert_eq!(polygon.boundary_points(), 22);
+/// assert_eq!(polygon.interior_points(), 20);
+/// assert_eq!(polygon.enclosed_points(), 42);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Polygon {
+    vertices: Vec<(i128, i128)>,
+}
+
+impl Polygon {
+    /// A polygon through `vertices` in order, closed by an edge from the last vertex back to the
+    /// first one.
+    pub fn from_vertices(vertices: impl IntoIterator<Item = (i64, i64)>) -> Polygon {
+        Polygon {
+            vertices: vertices
+                .into_iter()
+                .map(|(a, b)| (a as i128, b as i128))
+                .collect(),
+        }
+    }
+
+    /// The polygon traced by taking `steps` from `start` in (row, col) coordinates. The steps
+    /// must end up back at `start`.
+    pub fn from_steps(start: Idx, steps: impl IntoIterator<Item = (Dir, i64)>) -> Result<Polygon> {
+        let start = (start.0 as i128, start.1 as i128);
+        let mut vertices = vec![start];
+        let mut pos = start;
+        for (dir, len) in steps {
+            let (dr, dc) = dir.offset();
+            pos = (
+                pos.0 + dr as i128 * len as i128,
+                pos.1 + dc as i128 * len as i128,
+            );
+            vertices.push(pos);
+        }
+
+        if pos != start {
+            return Err(eyre!(
+                "polygon is not closed: steps end at {:?}, but started at {:?}",
+                pos,
+                start
+            ));
+        }
+
+        vertices.pop();
+        Ok(Polygon { vertices })
+    }
+
+    pub fn vertices(&self) -> &[(i128, i128)] {
+        &self.vertices
+    }
+
+    fn edges(&self) -> impl Iterator<Item = ((i128, i128), (i128, i128))> + '_ {
+        self.vertices.iter().copied().circular_tuple_windows()
+    }
+
+    /// Twice the signed area, by the shoelace formula. It is positive if the vertices go
+    /// clockwise in (row, col) coordinates, and negative if they go counterclockwise.
+    pub fn double_signed_area(&self) -> i128 {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+
+        self.edges().map(|(a, b)| a.1 * b.0 - a.0 * b.1).sum()
+    }
+
+    /// The area, rounded down for polygons with an area of a half-integer.
+    pub fn area(&self) -> i128 {
+        self.double_signed_area().abs() / 2
+    }
+
+    /// The number of lattice points on the edges of the polygon.
+    pub fn boundary_points(&self) -> i128 {
+        match self.vertices[..] {
+            [] => 0,
+            [_] => 1,
+            // The edge there and the edge back are the same segment
+            [a, b] => gcd(b.0 - a.0, b.1 - a.1) + 1,
+            _ => self.edges().map(|(a, b)| gcd(b.0 - a.0, b.1 - a.1)).sum(),
+        }
+    }
+
+    /// The number of lattice points strictly inside the polygon, by Pick's theorem. Degenerate
+    /// polygons, such as ones whose vertices are all collinear, have none.
+    pub fn interior_points(&self) -> i128 {
+        if self.vertices.len() < 3 {
+            return 0;
+        }
+
+        // Pick's theorem assumes a simple polygon; edges that retrace each other overcount the
+        // boundary and would make the result negative
+        ((self.double_signed_area().abs() - self.boundary_points() + 2) / 2).max(0)
+    }
+
+    /// The number of lattice points inside or on the polygon. For a polygon traced along the
+    /// centers of grid cells, this is the number of cells it covers.
+    pub fn enclosed_points(&self) -> i128 {
+        self.interior_points() + self.boundary_points()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIG_PLAN: &str = "\
+R 6 (#70c710)
+D 5 (#0dc571)
+L 2 (#5713f0)
+D 2 (#d2c081)
+R 2 (#59c680)
+D 2 (#411b91)
+L 5 (#8ceee2)
+U 2 (#caa173)
+L 1 (#1b58a2)
+U 2 (#caa171)
+R 2 (#7807d2)
+U 3 (#a77fa3)
+L 2 (#015232)
+U 2 (#7a21e3)
+";
+
+    #[test]
+    pub fn test_lagoon() {
+        let steps = DIG_PLAN.lines().map(|line| {
+            let (dir, len, _) = line.split(' ').collect_tuple().unwrap();
+            (
+                Dir::from_char(dir.chars().next().unwrap()).unwrap(),
+                len.parse().unwrap(),
+            )
+        });
+        let lagoon = Polygon::from_steps((0, 0), steps).unwrap();
+        assert_eq!(lagoon.enclosed_points(), 62);
+
+        let steps = DIG_PLAN.lines().map(|line| {
+            let hex = &line[line.find('#').unwrap() + 1..line.len() - 1];
+            let len = i64::from_str_radix(&hex[..5], 16).unwrap();
+            (Dir::ALL[(hex.as_bytes()[5] - b'0' + 1) as usize % 4], len)
+        });
+        let lagoon = Polygon::from_steps((0, 0), steps).unwrap();
+        assert_eq!(lagoon.enclosed_points(), 952408144115);
+    }
+
+    #[test]
+    pub fn test_pick_matches_brute_force() {
+        let triangle = Polygon::from_vertices([(0, 0), (4, 0), (0, 4)]);
+        assert_eq!(triangle.double_signed_area(), -16);
+        assert_eq!(triangle.boundary_points(), 12);
+        assert_eq!(triangle.interior_points(), 3);
+
+        let reversed = Polygon::from_vertices([(0, 4), (4, 0), (0, 0)]);
+        assert_eq!(reversed.double_signed_area(), 16);
+
+        // Points (x, y) with 0 < x, 0 < y and 2x + 3y < 30
+        let triangle = Polygon::from_vertices([(0, 0), (15, 0), (0, 10)]);
+        let interior = (1..15)
+            .cartesian_product(1..10)
+            .filter(|&(x, y)| 2 * x + 3 * y < 30)
+            .count();
+        assert_eq!(triangle.interior_points(), interior as i128);
+        assert_eq!(triangle.boundary_points(), 15 + 10 + 5);
+    }
+
+    #[test]
+    pub fn test_degenerate_polygons() {
+        assert_eq!(Polygon::from_vertices([]).boundary_points(), 0);
+        assert_eq!(Polygon::from_vertices([(1, 1)]).boundary_points(), 1);
+
+        let segment = Polygon::from_vertices([(0, 0), (4, 6)]);
+        assert_eq!(segment.boundary_points(), 3);
+        assert_eq!(segment.enclosed_points(), 3);
+
+        let collinear = Polygon::from_vertices([(0, 0), (0, 1), (0, 2)]);
+        assert_eq!(collinear.interior_points(), 0);
+
+        let steps = [(Dir::Right, 1), (Dir::Right, 1), (Dir::Left, 2)];
+        let retraced = Polygon::from_steps((0, 0), steps).unwrap();
+        assert_eq!(retraced.interior_points(), 0);
+    }
+
+    #[test]
+    pub fn test_from_steps_rejects_open_polygon() {
+        let steps = [(Dir::Right, 2), (Dir::Down, 2), (Dir::Left, 1)];
+
+        assert!(Polygon::from_steps((0, 0), steps).is_err());
+    }
+}
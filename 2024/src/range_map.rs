@@ -0,0 +1,266 @@
+/// Piecewise-constant offset maps over integers
+use crate::*;
+use num_traits::{PrimInt, Signed};
+
+/// A map from integers to integers that adds a constant offset within each of a number of
+/// disjoint source intervals, and leaves all other values unchanged. This is the shape of the
+/// `"seed-to-soil map:"` style maps, which makes it possible to chain several of them into one.
+///
+/// The map is defined on `[T::min_value(), T::max_value())`.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let mut seed_to_soil = RangeMap::new();
+/// seed_to_soil.insert_mapping(50, 98, 2).unwrap();
+/// seed_to_soil.insert_mapping(52, 50, 48).unwrap();
+///
+/// assert_eq!(seed_to_soil.map(79), 81);
+/// assert_eq!(seed_to_soil.map(10), 10);
+///
+/// let seeds = IntervalSet::from(Interval::new(96, 101));
+/// assert_eq!(
+///     seed_to_soil.map_set(&seeds).intervals(),
+///     [Interval::new(50, 52), Interval::new(98, 101)],
+/// );
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct RangeMap<T> {
+    /// Sorted, disjoint source intervals and the offset added to values within them
+    pieces: Vec<(Interval<T>, T)>,
+}
+
+impl<T: PrimInt + Signed + Debug> RangeMap<T> {
+    pub fn new() -> Self {
+        RangeMap { pieces: vec![] }
+    }
+
+    pub fn pieces(&self) -> &[(Interval<T>, T)] {
+        &self.pieces
+    }
+
+    /// Adds `offset` to all values in `source`, which may not overlap an existing piece.
+    pub fn insert(&mut self, source: Interval<T>, offset: T) -> Result<()> {
+        if source.is_empty() {
+            return Ok(());
+        }
+
+        let idx = self.pieces.partition_point(|(i, _)| i.end <= source.start);
+        if let Some((existing, _)) = self.pieces.get(idx) {
+            if existing.overlaps(&source) {
+                return Err(eyre!("source range {:?} overlaps {:?}", source, existing));
+            }
+        }
+
+        // Values outside every piece already map to themselves
+        if offset.is_zero() {
+            return Ok(());
+        }
+
+        self.pieces.insert(idx, (source, offset));
+        Ok(())
+    }
+
+    /// Maps `len` values starting at `source_start` to values starting at `dest_start`, in the
+    /// order in which the puzzle inputs list them.
+    pub fn insert_mapping(&mut self, dest_start: T, source_start: T, len: T) -> Result<()> {
+        self.insert(
+            Interval::new(source_start, source_start + len),
+            dest_start - source_start,
+        )
+    }
+
+    pub fn map(&self, value: T) -> T {
+        let idx = self.pieces.partition_point(|(i, _)| i.end <= value);
+        match self.pieces.get(idx) {
+            Some(&(source, offset)) if source.contains(value) => value + offset,
+            _ => value,
+        }
+    }
+
+    /// Maps every value in `set`, splitting its intervals where the offset changes.
+    pub fn map_set(&self, set: &IntervalSet<T>) -> IntervalSet<T> {
+        let mut mapped = IntervalSet::new();
+        for interval in set.intervals() {
+            for (source, offset) in self.segments() {
+                let part = source.intersection(interval);
+                if !part.is_empty() {
+                    mapped.insert(part.shifted(offset));
+                }
+            }
+        }
+
+        mapped
+    }
+
+    /// The map that applies `self` first and then `then`.
+    pub fn compose(&self, then: &RangeMap<T>) -> RangeMap<T> {
+        let then_segments = then.segments();
+
+        let mut pieces: Vec<(Interval<T>, T)> = vec![];
+        for (source, offset) in self.segments() {
+            let image = source.shifted(offset);
+            for &(then_source, then_offset) in &then_segments {
+                let part = image.intersection(&then_source);
+                if part.is_empty() || (offset + then_offset).is_zero() {
+                    continue;
+                }
+
+                let piece = (part.shifted(-offset), offset + then_offset);
+                match pieces.last_mut() {
+                    Some((last, last_offset))
+                        if last.end == piece.0.start && *last_offset == piece.1 =>
+                    {
+                        last.end = piece.0.end
+                    }
+                    _ => pieces.push(piece),
+                }
+            }
+        }
+
+        RangeMap { pieces }
+    }
+
+    /// The inverse map, if this map is a bijection (no two values map to the same value).
+    pub fn invert(&self) -> Option<RangeMap<T>> {
+        let mut inverse: Vec<(Interval<T>, T)> = self
+            .segments()
+            .into_iter()
+            .map(|(source, offset)| (source.shifted(offset), -offset))
+            .collect();
+        inverse.sort_by_key(|(image, _)| image.start);
+
+        let is_bijection = inverse
+            .iter()
+            .tuple_windows()
+            .all(|((a, _), (b, _))| a.end == b.start);
+        if !is_bijection {
+            return None;
+        }
+
+        inverse.retain(|(_, offset)| !offset.is_zero());
+        Some(RangeMap { pieces: inverse })
+    }
+
+    /// The pieces together with the identity-mapped gaps in between them, covering the whole
+    /// domain.
+    fn segments(&self) -> Vec<(Interval<T>, T)> {
+        let mut segments = Vec::with_capacity(2 * self.pieces.len() + 1);
+        let mut start = T::min_value();
+        for &(source, offset) in &self.pieces {
+            if start < source.start {
+                segments.push((Interval::new(start, source.start), T::zero()));
+            }
+            segments.push((source, offset));
+            start = source.end;
+        }
+
+        if start < T::max_value() {
+            segments.push((Interval::new(start, T::max_value()), T::zero()));
+        }
+
+        segments
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const ALMANAC: &str = "
+        seed-to-soil map:
+        50 98 2
+        52 50 48
+
+        soil-to-fertilizer map:
+        0 15 37
+        37 52 2
+        39 0 15
+
+        fertilizer-to-water map:
+        49 53 8
+        0 11 42
+        42 0 7
+        57 7 4
+
+        water-to-light map:
+        88 18 7
+        18 25 70
+
+        light-to-temperature map:
+        45 77 23
+        81 45 19
+        68 64 13
+
+        temperature-to-humidity map:
+        0 69 1
+        1 0 69
+
+        humidity-to-location map:
+        60 56 37
+        56 93 4
+    ";
+
+    fn almanac_maps() -> Vec<RangeMap<i64>> {
+        labeled_sections(ALMANAC)
+            .unwrap()
+            .iter()
+            .map(|section| {
+                let mut map = RangeMap::new();
+                for line in &section.lines {
+                    let [dest, source, len] = parse_n(line).unwrap();
+                    map.insert_mapping(dest, source, len).unwrap();
+                }
+                map
+            })
+            .collect()
+    }
+
+    #[test]
+    pub fn test_composed_map_matches_chained_maps() {
+        let maps = almanac_maps();
+        let seed_to_location = maps
+            .iter()
+            .skip(1)
+            .fold(maps[0].clone(), |composed, map| composed.compose(map));
+
+        for seed in -10..150 {
+            let chained = maps.iter().fold(seed, |value, map| map.map(value));
+            assert_eq!(seed_to_location.map(seed), chained, "seed {}", seed);
+        }
+
+        let seeds: IntervalSet<i64> = [Interval::new(79, 79 + 14), Interval::new(55, 55 + 13)]
+            .into_iter()
+            .collect();
+        assert_eq!(seed_to_location.map_set(&seeds).min(), Some(46));
+    }
+
+    #[test]
+    pub fn test_invert() {
+        let maps = almanac_maps();
+
+        for map in &maps {
+            let inverse = map.invert().unwrap();
+            for value in -10..150 {
+                assert_eq!(inverse.map(map.map(value)), value);
+            }
+        }
+
+        let mut not_injective = RangeMap::new();
+        not_injective.insert_mapping(0, 10, 5).unwrap();
+        assert!(not_injective.invert().is_none());
+    }
+
+    #[test]
+    pub fn test_insert_rejects_overlap() {
+        let mut map = RangeMap::new();
+        map.insert_mapping(50, 98, 2).unwrap();
+
+        assert!(map.insert_mapping(0, 90, 9).is_err());
+        assert!(map.insert_mapping(0, 90, 8).is_ok());
+        assert!(map.insert_mapping(99, 99, 3).is_err());
+        assert!(map.insert_mapping(100, 100, 3).is_ok());
+        assert_eq!(map.pieces().len(), 2);
+    }
+}
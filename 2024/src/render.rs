@@ -0,0 +1,220 @@
+/// Rendering Array2D to text, for debugging
+use crate::*;
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Color {
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+}
+
+impl Color {
+    fn ansi_code(self) -> u8 {
+        match self {
+            Color::Red => 31,
+            Color::Green => 32,
+            Color::Yellow => 33,
+            Color::Blue => 34,
+            Color::Magenta => 35,
+            Color::Cyan => 36,
+        }
+    }
+}
+
+struct Overlay {
+    positions: HashSet<Idx>,
+    glyph: Option<char>,
+    color: Option<Color>,
+}
+
+/// A text rendering of an [`Array2D`], created with [`Array2D::render`].
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let grid = CharGrid::from_text("..#\n...\n#..").unwrap();
+/// let path = [(1, 0), (1, 1), (1, 2)];
+///
+/// let text = grid
+///     .render(|&c| c)
+///     .overlay(path, '*')
+///     .crop((1, 0), (2, 2))
+///     .to_string();
+///
+/// assert_eq!(text, "***\n#..\n");
+/// ```
+pub struct Render<'a, T, F> {
+    array: &'a Array2D<T>,
+    cell_to_char: F,
+    overlays: Vec<Overlay>,
+    rulers: bool,
+    colors: bool,
+    top_left: Idx,
+    bottom_right: Idx,
+}
+
+impl<T> Array2D<T> {
+    pub fn render<F>(&self, cell_to_char: F) -> Render<'_, T, F>
+    where
+        F: Fn(&T) -> char,
+    {
+        Render {
+            array: self,
+            cell_to_char,
+            overlays: Vec::new(),
+            rulers: false,
+            colors: true,
+            top_left: (0, 0),
+            bottom_right: (self.nrows() - 1, self.ncols() - 1),
+        }
+    }
+}
+
+impl<T, F> Render<'_, T, F>
+where
+    F: Fn(&T) -> char,
+{
+    /// Draws `glyph` instead of the cell at each of `positions`. Later overlays take precedence.
+    pub fn overlay(mut self, positions: impl IntoIterator<Item = Idx>, glyph: char) -> Self {
+        self.overlays.push(Overlay {
+            positions: positions.into_iter().collect(),
+            glyph: Some(glyph),
+            color: None,
+        });
+        self
+    }
+
+    /// Colors the cells at each of `positions`. Later overlays take precedence.
+    pub fn highlight(mut self, positions: impl IntoIterator<Item = Idx>, color: Color) -> Self {
+        self.overlays.push(Overlay {
+            positions: positions.into_iter().collect(),
+            glyph: None,
+            color: Some(color),
+        });
+        self
+    }
+
+    /// Prefixes every row with its row index and adds a header with column indices.
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    /// Omits ANSI color codes, e.g. when writing to a file.
+    pub fn without_colors(mut self) -> Self {
+        self.colors = false;
+        self
+    }
+
+    /// Only renders the cells between `top_left` and `bottom_right` (inclusive), clamped to the
+    /// bounds of the array.
+    pub fn crop(mut self, top_left: Idx, bottom_right: Idx) -> Self {
+        self.top_left = (top_left.0.max(0), top_left.1.max(0));
+        self.bottom_right = (
+            bottom_right.0.min(self.array.nrows() - 1),
+            bottom_right.1.min(self.array.ncols() - 1),
+        );
+        self
+    }
+
+    fn cell(&self, pos: Idx) -> (char, Option<Color>) {
+        let mut glyph = (self.cell_to_char)(&self.array[pos]);
+        let mut color = None;
+        for overlay in &self.overlays {
+            if overlay.positions.contains(&pos) {
+                glyph = overlay.glyph.unwrap_or(glyph);
+                color = overlay.color.or(color);
+            }
+        }
+
+        (glyph, color)
+    }
+}
+
+fn num_digits(n: i64) -> usize {
+    n.max(0).to_string().len()
+}
+
+impl<T, F> Display for Render<'_, T, F>
+where
+    F: Fn(&T) -> char,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (top, left) = self.top_left;
+        let (bottom, right) = self.bottom_right;
+        let row_label_width = num_digits(bottom);
+
+        if self.rulers {
+            // Column indices are written vertically, one digit per header line
+            let col_label_width = num_digits(right);
+            for digit in 0..col_label_width {
+                write!(f, "{:width$} ", "", width = row_label_width)?;
+                for col in left..=right {
+                    let label = format!("{:>width$}", col, width = col_label_width);
+                    write!(f, "{}", &label[digit..digit + 1])?;
+                }
+                writeln!(f)?;
+            }
+        }
+
+        for row in top..=bottom {
+            if self.rulers {
+                write!(f, "{:>width$} ", row, width = row_label_width)?;
+            }
+
+            for col in left..=right {
+                match self.cell((row, col)) {
+                    (glyph, Some(color)) if self.colors => {
+                        write!(f, "\x1b[{}m{}\x1b[0m", color.ansi_code(), glyph)?
+                    }
+                    (glyph, _) => write!(f, "{}", glyph)?,
+                }
+            }
+
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::*;
+
+    #[test]
+    pub fn test_render_with_rulers() {
+        let grid = Array2D::from_shape_vec((2, 11), vec![false; 22]);
+
+        let text = grid
+            .render(|&b| if b { '#' } else { '.' })
+            .rulers()
+            .to_string();
+
+        assert_eq!(
+            text,
+            concat!(
+                "            1\n",
+                "  01234567890\n",
+                "0 ...........\n",
+                "1 ...........\n",
+            )
+        );
+    }
+
+    #[test]
+    pub fn test_render_with_colors() {
+        let grid = CharGrid::from_text("ab\ncd").unwrap();
+
+        let text = grid.render(|&c| c).highlight([(0, 1)], Color::Red);
+        assert_eq!(text.to_string(), "a\x1b[31mb\x1b[0m\ncd\n");
+
+        let text = text.without_colors();
+        assert_eq!(text.to_string(), "ab\ncd\n");
+    }
+}
@@ -0,0 +1,8 @@
+/// A small deterministic pseudo-random number generator (xorshift64) for generating test cases.
+/// `state` must not be zero.
+pub(crate) fn random(state: &mut u64) -> u64 {
+    *state ^= *state << 13;
+    *state ^= *state >> 7;
+    *state ^= *state << 17;
+    *state
+}
@@ -0,0 +1,503 @@
+/// 3D integer vectors, the rotations of a cube and point cloud registration
+use crate::*;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub struct Vec3 {
+    pub x: i64,
+    pub y: i64,
+    pub z: i64,
+}
+
+impl Vec3 {
+    pub const ZERO: Vec3 = Vec3 { x: 0, y: 0, z: 0 };
+
+    pub fn new(x: i64, y: i64, z: i64) -> Self {
+        Vec3 { x, y, z }
+    }
+
+    pub fn to_array(self) -> [i64; 3] {
+        [self.x, self.y, self.z]
+    }
+
+    pub fn manhattan(&self, other: &Vec3) -> i64 {
+        (self.x - other.x).abs() + (self.y - other.y).abs() + (self.z - other.z).abs()
+    }
+
+    pub fn squared_distance(&self, other: &Vec3) -> i64 {
+        let d = *self - *other;
+        d.x * d.x + d.y * d.y + d.z * d.z
+    }
+}
+
+impl From<[i64; 3]> for Vec3 {
+    fn from([x, y, z]: [i64; 3]) -> Self {
+        Vec3 { x, y, z }
+    }
+}
+
+impl Add for Vec3 {
+    type Output = Vec3;
+
+    fn add(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x + rhs.x, self.y + rhs.y, self.z + rhs.z)
+    }
+}
+
+impl AddAssign for Vec3 {
+    fn add_assign(&mut self, rhs: Vec3) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec3 {
+    type Output = Vec3;
+
+    fn sub(self, rhs: Vec3) -> Vec3 {
+        Vec3::new(self.x - rhs.x, self.y - rhs.y, self.z - rhs.z)
+    }
+}
+
+impl SubAssign for Vec3 {
+    fn sub_assign(&mut self, rhs: Vec3) {
+        *self = *self - rhs;
+    }
+}
+
+impl Neg for Vec3 {
+    type Output = Vec3;
+
+    fn neg(self) -> Vec3 {
+        Vec3::new(-self.x, -self.y, -self.z)
+    }
+}
+
+impl Mul<i64> for Vec3 {
+    type Output = Vec3;
+
+    fn mul(self, rhs: i64) -> Vec3 {
+        Vec3::new(self.x * rhs, self.y * rhs, self.z * rhs)
+    }
+}
+
+/// One of the 24 rotations that map the axes onto each other, as a rotation matrix.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// let quarter_turn = Rotation::all()
+///     .into_iter()
+///     .find(|r| r.apply(Vec3::new(1, 0, 0)) == Vec3::new(0, 1, 0) && r.apply(Vec3::new(0, 0, 1)) == Vec3::new(0, 0, 1))
+///     .unwrap();
+///
+/// assert_eq!(quarter_turn.apply(Vec3::new(1, 2, 3)), Vec3::new(-2, 1, 3));
+/// assert_eq!(quarter_turn.compose(&quarter_turn.inverse()), Rotation::IDENTITY);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Rotation {
+    pub matrix: [[i64; 3]; 3],
+}
+
+impl Rotation {
+    pub const IDENTITY: Rotation = Rotation {
+        matrix: [[1, 0, 0], [0, 1, 0], [0, 0, 1]],
+    };
+
+    /// All 24 rotations, starting with the identity.
+    pub fn all() -> Vec<Rotation> {
+        let mut rotations = vec![];
+        for axes in (0..3).permutations(3) {
+            for signs in 0..8 {
+                let mut matrix = [[0; 3]; 3];
+                for (row, &axis) in axes.iter().enumerate() {
+                    matrix[row][axis] = if signs & (1 << row) == 0 { 1 } else { -1 };
+                }
+
+                let rotation = Rotation { matrix };
+                if rotation.determinant() == 1 {
+                    rotations.push(rotation);
+                }
+            }
+        }
+
+        rotations
+    }
+
+    fn determinant(&self) -> i64 {
+        let m = &self.matrix;
+        m[0][0] * (m[1][1] * m[2][2] - m[1][2] * m[2][1])
+            - m[0][1] * (m[1][0] * m[2][2] - m[1][2] * m[2][0])
+            + m[0][2] * (m[1][0] * m[2][1] - m[1][1] * m[2][0])
+    }
+
+    pub fn apply(&self, v: Vec3) -> Vec3 {
+        let [a, b, c] = self
+            .matrix
+            .map(|row| row[0] * v.x + row[1] * v.y + row[2] * v.z);
+        Vec3::new(a, b, c)
+    }
+
+    /// The rotation that applies `other` first, and then `self`.
+    pub fn compose(&self, other: &Rotation) -> Rotation {
+        let mut matrix = [[0; 3]; 3];
+        for (row, cells) in matrix.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                *cell = (0..3)
+                    .map(|k| self.matrix[row][k] * other.matrix[k][col])
+                    .sum();
+            }
+        }
+
+        Rotation { matrix }
+    }
+
+    pub fn inverse(&self) -> Rotation {
+        // Rotation matrices are orthogonal, so the inverse is the transpose
+        let mut matrix = [[0; 3]; 3];
+        for (row, cells) in matrix.iter_mut().enumerate() {
+            for (col, cell) in cells.iter_mut().enumerate() {
+                *cell = self.matrix[col][row];
+            }
+        }
+
+        Rotation { matrix }
+    }
+}
+
+impl Mul<Vec3> for Rotation {
+    type Output = Vec3;
+
+    fn mul(self, rhs: Vec3) -> Vec3 {
+        self.apply(rhs)
+    }
+}
+
+/// Finds the rotation and translation that map at least `min_overlap` of `points` onto points of
+/// `reference`, so that `rotation.apply(p) + translation` is in `reference` for those points.
+///
+/// Both point clouds are first compared by the distances between their points, which do not
+/// change under rotation and translation, so that clouds that cannot overlap are rejected quickly.
+pub fn register(
+    reference: &[Vec3],
+    points: &[Vec3],
+    min_overlap: usize,
+) -> Option<(Rotation, Vec3)> {
+    if min_overlap == 0 {
+        return Some((Rotation::IDENTITY, Vec3::ZERO));
+    }
+
+    let reference_distances = pairwise_distances(reference);
+    let shared_distances: usize = pairwise_distances(points)
+        .iter()
+        .map(|(d, &count)| count.min(reference_distances.get(d).copied().unwrap_or(0)))
+        .sum();
+    if shared_distances < min_overlap * (min_overlap - 1) / 2 {
+        return None;
+    }
+
+    let mut votes: HashMap<Vec3, usize> = HashMap::default();
+    for rotation in Rotation::all() {
+        votes.clear();
+        for &p in points {
+            let rotated = rotation.apply(p);
+            for &r in reference {
+                let count = votes.entry(r - rotated).or_default();
+                *count += 1;
+                if *count >= min_overlap {
+                    return Some((rotation, r - rotated));
+                }
+            }
+        }
+    }
+
+    None
+}
+
+fn pairwise_distances(points: &[Vec3]) -> HashMap<i64, usize> {
+    let mut distances = HashMap::default();
+    for (a, b) in points.iter().tuple_combinations() {
+        *distances.entry(a.squared_distance(b)).or_default() += 1;
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_util::random;
+
+    const SCANNER_REPORTS: &str = "\
+--- scanner 0 ---
+404,-588,-901
+528,-643,409
+-838,591,734
+390,-675,-793
+-537,-823,-458
+-485,-357,347
+-345,-311,381
+-661,-816,-575
+-876,649,763
+-618,-824,-621
+553,345,-567
+474,580,667
+-447,-329,318
+-584,868,-557
+544,-627,-890
+564,392,-477
+455,729,728
+-892,524,684
+-689,845,-530
+423,-701,434
+7,-33,-71
+630,319,-379
+443,580,662
+-789,900,-551
+459,-707,401
+
+--- scanner 1 ---
+686,422,578
+605,423,415
+515,917,-361
+-336,658,858
+95,138,22
+-476,619,847
+-340,-569,-846
+567,-361,727
+-460,603,-452
+669,-402,600
+729,430,532
+-500,-761,534
+-322,571,750
+-466,-666,-811
+-429,-592,574
+-355,545,-477
+703,-491,-529
+-328,-685,520
+413,935,-424
+-391,539,-444
+586,-435,557
+-364,-763,-893
+807,-499,-711
+755,-354,-619
+553,889,-390
+
+--- scanner 2 ---
+649,640,665
+682,-795,504
+-784,533,-524
+-644,584,-595
+-588,-843,648
+-30,6,44
+-674,560,763
+500,723,-460
+609,671,-379
+-555,-800,653
+-675,-892,-343
+697,-426,-610
+578,704,681
+493,664,-388
+-671,-858,530
+-667,343,800
+571,-461,-707
+-138,-166,112
+-889,563,-600
+646,-828,498
+640,759,510
+-630,509,768
+-681,-892,-333
+673,-379,-804
+-742,-814,-386
+577,-820,562
+
+--- scanner 3 ---
+-589,542,597
+605,-692,669
+-500,565,-823
+-660,373,557
+-458,-679,-417
+-488,449,543
+-626,468,-788
+338,-750,-386
+528,-832,-391
+562,-778,733
+-938,-730,414
+543,643,-506
+-524,371,-870
+407,773,750
+-104,29,83
+378,-903,-323
+-778,-728,485
+426,699,580
+-438,-605,-362
+-469,-447,-387
+509,732,623
+647,635,-688
+-868,-804,481
+614,-800,639
+595,780,-596
+
+--- scanner 4 ---
+727,592,562
+-293,-554,779
+441,611,-461
+-714,465,-776
+-743,427,-804
+-660,-479,-426
+832,-632,460
+927,-485,-438
+408,393,-506
+466,436,-512
+110,16,151
+-258,-428,682
+-393,719,612
+-211,-452,876
+808,-476,-593
+-575,615,604
+-485,667,467
+-680,325,-822
+-627,-443,-432
+872,-547,-609
+833,512,582
+807,604,487
+839,-516,451
+891,-625,532
+-652,-548,-490
+30,-46,-14
+";
+
+    fn parse_reports(input: &str) -> Vec<Vec<Vec3>> {
+        sections(input)
+            .iter()
+            .map(|report| {
+                report.lines[1..]
+                    .iter()
+                    .map(|line| Vec3::from(parse_n::<i64, 3>(line).unwrap()))
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Aligns all reports to the first one, returning the beacons and the scanner positions
+    fn assemble(reports: &[Vec<Vec3>]) -> (HashSet<Vec3>, Vec<Vec3>) {
+        let mut beacons: HashSet<Vec3> = reports[0].iter().copied().collect();
+        let mut scanners = vec![Vec3::ZERO];
+        let mut aligned = vec![reports[0].clone()];
+        let mut unaligned: VecDeque<&Vec<Vec3>> = reports.iter().skip(1).collect();
+        while let Some(report) = unaligned.pop_front() {
+            let found = aligned
+                .iter()
+                .find_map(|reference| register(reference, report, 12));
+
+            match found {
+                Some((rotation, translation)) => {
+                    let points = report
+                        .iter()
+                        .map(|&p| rotation.apply(p) + translation)
+                        .collect_vec();
+                    beacons.extend(points.iter().copied());
+                    scanners.push(translation);
+                    aligned.push(points);
+                }
+                None => unaligned.push_back(report),
+            }
+        }
+
+        (beacons, scanners)
+    }
+
+    #[test]
+    pub fn test_rotation_group() {
+        let rotations = Rotation::all();
+        assert_eq!(rotations.len(), 24);
+        assert_eq!(rotations[0], Rotation::IDENTITY);
+
+        let distinct: HashSet<Rotation> = rotations.iter().copied().collect();
+        assert_eq!(distinct.len(), 24);
+
+        let v = Vec3::new(1, 2, 3);
+        for a in &rotations {
+            assert!(distinct.contains(&a.inverse()));
+            assert_eq!(a.inverse().apply(a.apply(v)), v);
+
+            for b in &rotations {
+                let ab = a.compose(b);
+                assert!(distinct.contains(&ab));
+                assert_eq!(ab.apply(v), a.apply(b.apply(v)));
+            }
+        }
+    }
+
+    #[test]
+    pub fn test_assemble_scanner_reports() {
+        let reports = parse_reports(SCANNER_REPORTS);
+
+        let (beacons, scanners) = assemble(&reports);
+
+        assert_eq!(beacons.len(), 79);
+        assert_eq!(scanners[1], Vec3::new(68, -1246, -43));
+
+        let max_distance = scanners
+            .iter()
+            .tuple_combinations()
+            .map(|(a, b)| a.manhattan(b))
+            .max();
+        assert_eq!(max_distance, Some(3621));
+    }
+
+    /// Assembles reports the size of a real puzzle input: 36 scanners with 26 beacons each, in a
+    /// chain where each scanner shares 13 beacons with the next one. This is slow in a debug
+    /// build, so run it with `cargo test --release -- --ignored`.
+    #[test]
+    #[ignore]
+    pub fn bench_assemble_puzzle_sized_input() {
+        let mut rng = 0x5eed;
+        let mut coordinate = |range: i64| (random(&mut rng) % range as u64) as i64;
+
+        let segments = (0..37)
+            .map(|k| {
+                (0..13)
+                    .map(|_| {
+                        let x = k * 1000 + coordinate(1000);
+                        Vec3::new(x, coordinate(2000) - 1000, coordinate(2000) - 1000)
+                    })
+                    .collect_vec()
+            })
+            .collect_vec();
+        let scanners = (0..36)
+            .map(|k| {
+                Vec3::new(
+                    k * 1000 + coordinate(1000),
+                    coordinate(200),
+                    coordinate(200),
+                )
+            })
+            .collect_vec();
+        let rotations = Rotation::all();
+        let reports = (0..36)
+            .map(|k| {
+                let rotation = rotations[coordinate(24) as usize];
+                segments[k]
+                    .iter()
+                    .chain(&segments[k + 1])
+                    .map(|&p| rotation.apply(p - scanners[k]))
+                    .collect_vec()
+            })
+            .collect_vec();
+
+        let (beacons, found_scanners) = assemble(&reports);
+
+        assert_eq!(beacons.len(), 37 * 13);
+        assert_eq!(found_scanners.len(), 36);
+    }
+
+    #[test]
+    pub fn test_register_rejects_too_little_overlap() {
+        let reports = parse_reports(SCANNER_REPORTS);
+
+        assert!(register(&reports[0], &reports[1], 12).is_some());
+        assert!(register(&reports[0], &reports[1], 13).is_none());
+        assert!(register(&reports[0], &reports[2], 12).is_none());
+    }
+}
@@ -0,0 +1,389 @@
+/// A small virtual machine for puzzles that run assembly-like programs
+use crate::*;
+use std::hash::Hash;
+
+/// An instruction of a program run by a [`Machine`].
+pub trait Instruction {
+    /// The registers (or other state) that instructions operate on.
+    type Registers;
+
+    /// The number of cycles it takes to execute the instruction.
+    fn cycles(&self) -> u64 {
+        1
+    }
+
+    /// Applies the instruction to the registers, which happens at the end of its last cycle.
+    fn execute(&self, registers: &mut Self::Registers) -> Jump;
+}
+
+/// Where to continue after executing an instruction.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Jump {
+    /// The next instruction
+    Next,
+    /// The instruction at an offset from the current one
+    Relative(i64),
+    /// The instruction at an index
+    Absolute(usize),
+    /// Stops the machine
+    Halt,
+}
+
+/// Why the machine stopped running.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Status {
+    /// The machine can continue running.
+    Running,
+    /// The program counter left the program, or a [`Jump::Halt`] was executed.
+    Halted,
+    /// The cycle with this number was completed, and it has a breakpoint.
+    Breakpoint(u64),
+    /// The instruction at `pc` is about to be executed in the same state as before, so the
+    /// machine would run forever.
+    Loop { pc: usize },
+}
+
+/// A machine that runs a program of instructions, one cycle at a time.
+///
+/// Example:
+/// ```
+/// use aoc2024::*;
+///
+/// enum Op {
+///     Acc(i64),
+///     Jmp(i64),
+///     Nop,
+/// }
+///
+/// impl Instruction for Op {
+///     type Registers = i64;
+///
+///     fn execute(&self, acc: &mut i64) -> Jump {
+///         match self {
+///             Op::Acc(value) => *acc += value,
+///             Op::Jmp(offset) => return Jump::Relative(*offset),
+///             Op::Nop => {}
+///         }
+///         Jump::Next
+///     }
+/// }
+///
+/// let mut machine = Machine::new(vec![Op::Acc(3), Op::Nop, Op::Jmp(-2)], 0);
+///
+/// // Stop when an instruction is about to be executed a second time
+/// assert_eq!(machine.run_until_repeated(|m| m.pc()), Status::Loop { pc: 0 });
+/// assert_eq!(machine.registers, 3);
+/// ```
+pub struct Machine<I: Instruction> {
+    program: Vec<I>,
+    pub registers: I::Registers,
+    pc: usize,
+    /// The number of completed cycles
+    cycle: u64,
+    /// The number of cycles already spent on the current instruction
+    elapsed: u64,
+    halted: bool,
+    breakpoints: HashSet<u64>,
+}
+
+impl<I: Instruction> Machine<I> {
+    pub fn new(program: Vec<I>, registers: I::Registers) -> Self {
+        Machine {
+            program,
+            registers,
+            pc: 0,
+            cycle: 0,
+            elapsed: 0,
+            halted: false,
+            breakpoints: HashSet::default(),
+        }
+    }
+
+    pub fn program(&self) -> &[I] {
+        &self.program
+    }
+
+    pub fn pc(&self) -> usize {
+        self.pc
+    }
+
+    /// The number of completed cycles.
+    pub fn cycle(&self) -> u64 {
+        self.cycle
+    }
+
+    pub fn is_halted(&self) -> bool {
+        self.halted || self.pc >= self.program.len()
+    }
+
+    /// Stops running once cycle `cycle` (counting from 1) has completed.
+    pub fn add_breakpoint(&mut self, cycle: u64) {
+        self.breakpoints.insert(cycle);
+    }
+
+    pub fn remove_breakpoint(&mut self, cycle: u64) {
+        self.breakpoints.remove(&cycle);
+    }
+
+    /// Runs a single cycle, executing the current instruction if this is its last cycle.
+    pub fn tick(&mut self) -> Status {
+        if self.is_halted() {
+            return Status::Halted;
+        }
+
+        self.cycle += 1;
+        self.elapsed += 1;
+
+        let instruction = &self.program[self.pc];
+        if self.elapsed >= instruction.cycles() {
+            self.elapsed = 0;
+            match instruction.execute(&mut self.registers) {
+                Jump::Next => self.pc += 1,
+                Jump::Relative(offset) => match self.pc.checked_add_signed(offset as isize) {
+                    Some(pc) => self.pc = pc,
+                    None => self.halted = true,
+                },
+                Jump::Absolute(pc) => self.pc = pc,
+                Jump::Halt => self.halted = true,
+            }
+        }
+
+        if self.is_halted() {
+            Status::Halted
+        } else if self.breakpoints.contains(&self.cycle) {
+            Status::Breakpoint(self.cycle)
+        } else {
+            Status::Running
+        }
+    }
+
+    /// Runs until the current instruction has been executed, or a breakpoint is hit.
+    pub fn step(&mut self) -> Status {
+        loop {
+            let status = self.tick();
+            if status != Status::Running || self.elapsed == 0 {
+                return status;
+            }
+        }
+    }
+
+    /// Executes instructions until `condition` holds between two instructions, or the machine
+    /// stops for another reason.
+    pub fn run_until(&mut self, mut condition: impl FnMut(&Self) -> bool) -> Status {
+        loop {
+            if condition(self) {
+                return Status::Running;
+            }
+
+            let status = self.step();
+            if status != Status::Running {
+                return status;
+            }
+        }
+    }
+
+    /// Runs cycle by cycle, calling `observer` with the number of each cycle (counting from 1)
+    /// and the registers during that cycle, before the instruction in it has been executed.
+    pub fn run_observed(&mut self, mut observer: impl FnMut(u64, &I::Registers)) -> Status {
+        loop {
+            if self.is_halted() {
+                return Status::Halted;
+            }
+
+            observer(self.cycle + 1, &self.registers);
+            let status = self.tick();
+            if status != Status::Running {
+                return status;
+            }
+        }
+    }
+
+    /// Like [`Machine::run`], but detects loops by a key computed from the machine between
+    /// instructions. For example, keying by [`Machine::pc`] stops as soon as any instruction is
+    /// about to be executed for the second time.
+    pub fn run_until_repeated<K: Hash + Eq>(&mut self, mut key: impl FnMut(&Self) -> K) -> Status {
+        let mut seen: HashSet<K> = HashSet::default();
+        loop {
+            if self.elapsed == 0 && !seen.insert(key(self)) {
+                return Status::Loop { pc: self.pc };
+            }
+
+            let status = self.step();
+            if status != Status::Running {
+                return status;
+            }
+        }
+    }
+}
+
+impl<I: Instruction> Machine<I>
+where
+    I::Registers: Clone + Hash + Eq,
+{
+    /// Runs until the machine halts or hits a breakpoint, or until it would start an instruction
+    /// with the same program counter and registers as before, which means it is stuck in a loop.
+    pub fn run(&mut self) -> Status {
+        self.run_until_repeated(|machine| (machine.pc, machine.registers.clone()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    enum Cpu {
+        Addx(i64),
+        Noop,
+    }
+
+    impl Instruction for Cpu {
+        type Registers = i64;
+
+        fn cycles(&self) -> u64 {
+            match self {
+                Cpu::Addx(_) => 2,
+                Cpu::Noop => 1,
+            }
+        }
+
+        fn execute(&self, x: &mut i64) -> Jump {
+            if let Cpu::Addx(value) = self {
+                *x += value;
+            }
+
+            Jump::Next
+        }
+    }
+
+    fn parse_cpu_program(input: &str) -> Vec<Cpu> {
+        input
+            .lines()
+            .map(|line| match line.split_once(' ') {
+                Some((_, value)) => Cpu::Addx(value.parse().unwrap()),
+                None => Cpu::Noop,
+            })
+            .collect()
+    }
+
+    const CPU_PROGRAM: &str = "\
+addx 15\naddx -11\naddx 6\naddx -3\naddx 5\naddx -1\naddx -8\naddx 13\naddx 4\nnoop\naddx -1
+addx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx 5\naddx -1\naddx -35\naddx 1\naddx 24
+addx -19\naddx 1\naddx 16\naddx -11\nnoop\nnoop\naddx 21\naddx -15\nnoop\nnoop\naddx -3\naddx 9
+addx 1\naddx -3\naddx 8\naddx 1\naddx 5\nnoop\nnoop\nnoop\nnoop\nnoop\naddx -36\nnoop\naddx 1
+addx 7\nnoop\nnoop\nnoop\naddx 2\naddx 6\nnoop\nnoop\nnoop\nnoop\nnoop\naddx 1\nnoop\nnoop
+addx 7\naddx 1\nnoop\naddx -13\naddx 13\naddx 7\nnoop\naddx 1\naddx -33\nnoop\nnoop\nnoop
+addx 2\nnoop\nnoop\nnoop\naddx 8\nnoop\naddx -1\naddx 2\naddx 1\nnoop\naddx 17\naddx -9\naddx 1
+addx 1\naddx -3\naddx 11\nnoop\nnoop\naddx 1\nnoop\naddx 1\nnoop\nnoop\naddx -13\naddx -19
+addx 1\naddx 3\naddx 26\naddx -30\naddx 12\naddx -1\naddx 3\naddx 1\nnoop\nnoop\nnoop\naddx -9
+addx 18\naddx 1\naddx 2\nnoop\nnoop\naddx 9\nnoop\nnoop\nnoop\naddx -1\naddx 2\naddx -37
+addx 1\naddx 3\nnoop\naddx 15\naddx -21\naddx 22\naddx -6\naddx 1\nnoop\naddx 2\naddx 1\nnoop
+addx -10\nnoop\nnoop\naddx 20\naddx 1\naddx 2\naddx 2\naddx -6\naddx -11\nnoop\nnoop\nnoop";
+
+    #[test]
+    pub fn test_cpu_observer() {
+        let mut cpu = Machine::new(parse_cpu_program(CPU_PROGRAM), 1);
+
+        let mut signal_strength = 0;
+        let mut screen = String::new();
+        let status = cpu.run_observed(|cycle, &x| {
+            if cycle % 40 == 20 {
+                signal_strength += cycle as i64 * x;
+            }
+
+            let col = ((cycle - 1) % 40) as i64;
+            screen.push(if (col - x).abs() <= 1 { '#' } else { '.' });
+            if col == 39 {
+                screen.push('\n');
+            }
+        });
+
+        assert_eq!(status, Status::Halted);
+        assert_eq!(cpu.cycle(), 240);
+        assert_eq!(signal_strength, 13140);
+        assert_eq!(
+            screen.lines().next(),
+            Some("##..##..##..##..##..##..##..##..##..##..")
+        );
+    }
+
+    #[test]
+    pub fn test_breakpoints_and_steps() {
+        let mut cpu = Machine::new(parse_cpu_program(CPU_PROGRAM), 1);
+        cpu.add_breakpoint(19);
+        cpu.add_breakpoint(20);
+
+        assert_eq!(cpu.run(), Status::Breakpoint(19));
+        assert_eq!((cpu.pc(), cpu.registers), (10, 21));
+
+        // Cycle 20 is the first cycle of an addx, so it stops before the addx is executed
+        assert_eq!(cpu.step(), Status::Breakpoint(20));
+        assert_eq!((cpu.pc(), cpu.registers), (10, 21));
+        assert_eq!(cpu.step(), Status::Running);
+        assert_eq!((cpu.cycle(), cpu.pc(), cpu.registers), (21, 11, 20));
+
+        assert_eq!(cpu.run_until(|cpu| cpu.registers < 5), Status::Running);
+        assert_eq!((cpu.pc(), cpu.registers), (20, 1));
+    }
+
+    enum Handheld {
+        Acc(i64),
+        Jmp(i64),
+        Nop,
+    }
+
+    impl Instruction for Handheld {
+        type Registers = i64;
+
+        fn execute(&self, acc: &mut i64) -> Jump {
+            match self {
+                Handheld::Acc(value) => *acc += value,
+                Handheld::Jmp(offset) => return Jump::Relative(*offset),
+                Handheld::Nop => {}
+            }
+
+            Jump::Next
+        }
+    }
+
+    #[test]
+    pub fn test_loop_detection() {
+        use Handheld::*;
+        let program = vec![
+            Nop,
+            Acc(1),
+            Jmp(4),
+            Acc(3),
+            Jmp(-3),
+            Acc(-99),
+            Acc(1),
+            Jmp(-4),
+            Acc(6),
+        ];
+
+        let mut handheld = Machine::new(program, 0);
+        assert_eq!(
+            handheld.run_until_repeated(|m| m.pc()),
+            Status::Loop { pc: 1 }
+        );
+        assert_eq!(handheld.registers, 5);
+
+        let fixed = vec![
+            Nop,
+            Acc(1),
+            Jmp(4),
+            Acc(3),
+            Jmp(-3),
+            Acc(-99),
+            Acc(1),
+            Nop,
+            Acc(6),
+        ];
+        let mut handheld = Machine::new(fixed, 0);
+        assert_eq!(handheld.run(), Status::Halted);
+        assert_eq!(handheld.registers, 8);
+
+        let mut toggle = Machine::new(vec![Nop, Acc(2), Acc(-2), Jmp(-2)], 0);
+        assert_eq!(toggle.run(), Status::Loop { pc: 1 });
+        assert_eq!(toggle.cycle(), 4);
+    }
+}